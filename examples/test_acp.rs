@@ -19,18 +19,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = AgentConfig::new(agent.cli_path())
         .with_timeout(std::time::Duration::from_secs(30)); // 30 second timeout for test
 
-    // Run in a blocking thread with its own runtime (same as Tauri does)
-    let result = tokio::task::spawn_blocking(move || {
-        let rt = tokio::runtime::Builder::new_current_thread()
-            .enable_all()
-            .build()?;
-
-        rt.block_on(async {
-            let client = AcpClient::new(agent, config);
-            println!("Sending test prompt: 'What is 2+2? Reply with just the number.'");
-            client.send_prompt("What is 2+2? Reply with just the number.").await
-        })
-    }).await??;
+    let client = AcpClient::new(agent, config);
+    println!("Sending test prompt: 'What is 2+2? Reply with just the number.'");
+    let result = client.send_prompt("What is 2+2? Reply with just the number.").await?;
 
     println!("Response: {}", result);
     println!("SUCCESS!");
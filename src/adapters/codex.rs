@@ -7,38 +7,47 @@
 //!
 //! Usage modes:
 //! - Interactive: `codex`
-//! - Non-interactive: `codex -q "prompt"` or `codex --quiet "prompt"`
-//! - Full auto: `codex --approval-mode full-auto "prompt"`
+//! - Non-interactive: `codex exec "prompt"` (`-` reads the prompt from stdin)
+//! - JSON events: `codex exec --json -`
+//! - Full auto: `codex exec --full-auto -`
 
-use std::time::Duration;
-use crate::domain::Agent;
+use std::collections::HashSet;
+use serde_json::{json, Value};
+
+use crate::domain::{
+    Agent, AgentTransport, OutputParser, PlanEntry, PlanEntryPriority, PlanEntryStatus, SessionEvent,
+    StopReason, TokenUsage, ToolCall, ToolCallLocation, ToolCallStatus,
+};
 
 /// Approval mode for Codex CLI
+///
+/// `codex exec` never stops to ask, so each mode maps to a sandbox.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum CodexApprovalMode {
-    /// Suggest mode - requires approval for all actions
+    /// Suggest mode - read-only sandbox, no edits
     #[default]
     Suggest,
-    /// Auto-edit mode - automatically approves file edits
+    /// Auto-edit mode - may edit files in the workspace
     AutoEdit,
-    /// Full-auto mode - automatically approves all actions (network disabled)
+    /// Full-auto mode - `--full-auto` (workspace writes, network disabled)
     FullAuto,
 }
 
 impl CodexApprovalMode {
-    fn as_str(&self) -> &'static str {
-        match self {
-            CodexApprovalMode::Suggest => "suggest",
-            CodexApprovalMode::AutoEdit => "auto-edit",
-            CodexApprovalMode::FullAuto => "full-auto",
-        }
+    fn args(&self) -> Vec<String> {
+        let args: &[&str] = match self {
+            CodexApprovalMode::Suggest => &["--sandbox", "read-only"],
+            CodexApprovalMode::AutoEdit => &["--sandbox", "workspace-write"],
+            CodexApprovalMode::FullAuto => &["--full-auto"],
+        };
+        args.iter().map(|arg| arg.to_string()).collect()
     }
 }
 
 /// OpenAI Codex CLI agent implementation
 ///
 /// Note: Codex CLI does not support ACP protocol, so this agent
-/// uses the non-interactive quiet mode (-q) for programmatic access.
+/// runs `codex exec` once per prompt.
 #[derive(Debug, Clone)]
 pub struct CodexAgent {
     cli_path: String,
@@ -109,12 +118,17 @@ impl Agent for CodexAgent {
         self.chat_args()
     }
 
+    fn transport(&self) -> AgentTransport {
+        if self.json_output {
+            AgentTransport::StreamingCli
+        } else {
+            AgentTransport::Cli
+        }
+    }
+
     fn chat_args(&self) -> Vec<String> {
-        let mut args = vec![
-            "-q".to_string(), // Quiet/non-interactive mode
-            "--approval-mode".to_string(),
-            self.approval_mode.as_str().to_string(),
-        ];
+        let mut args = vec!["exec".to_string()];
+        args.extend(self.approval_mode.args());
 
         if let Some(ref model) = self.model {
             args.push("-m".to_string());
@@ -125,6 +139,8 @@ impl Agent for CodexAgent {
             args.push("--json".to_string());
         }
 
+        // Read the prompt from stdin
+        args.push("-".to_string());
         args
     }
 
@@ -132,19 +148,15 @@ impl Agent for CodexAgent {
         false
     }
 
-    fn session_init_delay(&self) -> Duration {
-        Duration::ZERO
-    }
-
-    fn post_prompt_delay(&self) -> Duration {
-        Duration::from_millis(100)
-    }
-
     fn process_response(&self, response: &str) -> String {
         // Strip ANSI codes and clean up output
         strip_ansi_codes(response)
     }
 
+    fn output_parser(&self) -> Option<Box<dyn OutputParser>> {
+        self.json_output.then(|| Box::new(CodexJsonParser::new()) as Box<dyn OutputParser>)
+    }
+
     fn environment(&self) -> Vec<(String, String)> {
         vec![
             // Suppress interactive UI elements
@@ -153,6 +165,153 @@ impl Agent for CodexAgent {
     }
 }
 
+/// Parser for the events of `codex exec --json`
+///
+/// Agent messages and reasoning become text, command runs, file changes, MCP
+/// tool calls and web searches become tool calls, and the to-do list becomes
+/// the plan. Other events and lines that aren't JSON are skipped.
+#[derive(Debug, Default)]
+pub struct CodexJsonParser {
+    /// IDs of the tool calls reported so far
+    tool_calls: HashSet<String>,
+    /// Whether answer text has been reported (later messages start a new paragraph)
+    has_text: bool,
+}
+
+impl CodexJsonParser {
+    /// Create a parser for one prompt
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Events for an item that started, changed or completed
+    fn parse_item(&mut self, item: &Value, completed: bool) -> Vec<SessionEvent> {
+        let text = item["text"].as_str().unwrap_or_default();
+        match item["type"].as_str().unwrap_or_default() {
+            "agent_message" if completed => {
+                let separator = if self.has_text { "\n\n" } else { "" };
+                self.has_text = true;
+                vec![SessionEvent::MessageChunk(format!("{}{}", separator, text))]
+            }
+            "reasoning" if completed => vec![SessionEvent::ThoughtChunk(format!("{}\n", text))],
+            "todo_list" => vec![SessionEvent::Plan(plan(item))],
+            "error" => vec![SessionEvent::Error(item["message"].as_str().unwrap_or_default().to_string())],
+            _ => {
+                let Some(mut call) = tool_call(item) else {
+                    return vec![];
+                };
+                if completed && call.status == ToolCallStatus::InProgress {
+                    call.status = ToolCallStatus::Completed;
+                }
+                if self.tool_calls.insert(call.id.clone()) {
+                    vec![SessionEvent::ToolCallStarted(call)]
+                } else {
+                    vec![SessionEvent::ToolCallUpdated(call)]
+                }
+            }
+        }
+    }
+}
+
+impl OutputParser for CodexJsonParser {
+    fn parse_line(&mut self, line: &str) -> Vec<SessionEvent> {
+        let Ok(event) = serde_json::from_str::<Value>(line) else {
+            return vec![];
+        };
+
+        match event["type"].as_str().unwrap_or_default() {
+            "item.started" | "item.updated" => self.parse_item(&event["item"], false),
+            "item.completed" => self.parse_item(&event["item"], true),
+            "turn.completed" => {
+                let usage = &event["usage"];
+                let input_tokens = usage["input_tokens"].as_u64().unwrap_or(0);
+                let output_tokens = usage["output_tokens"].as_u64().unwrap_or(0);
+                vec![
+                    SessionEvent::Usage(TokenUsage {
+                        input_tokens,
+                        output_tokens,
+                        total_tokens: input_tokens + output_tokens,
+                    }),
+                    SessionEvent::Stopped(StopReason::EndTurn),
+                ]
+            }
+            "turn.failed" => vec![SessionEvent::Error(
+                event["error"]["message"].as_str().unwrap_or("Codex failed").to_string(),
+            )],
+            "error" => vec![SessionEvent::Error(event["message"].as_str().unwrap_or_default().to_string())],
+            _ => vec![],
+        }
+    }
+}
+
+/// The tool call a Codex item stands for, if it is one
+fn tool_call(item: &Value) -> Option<ToolCall> {
+    let field = |name: &str| item[name].as_str().unwrap_or_default().to_string();
+    let mut call = ToolCall {
+        id: field("id"),
+        status: match item["status"].as_str() {
+            Some("completed") => ToolCallStatus::Completed,
+            Some("failed") | Some("declined") => ToolCallStatus::Failed,
+            _ => ToolCallStatus::InProgress,
+        },
+        ..Default::default()
+    };
+
+    match item["type"].as_str()? {
+        "command_execution" => {
+            call.title = field("command");
+            call.kind = Some("execute".to_string());
+            call.raw_input = Some(json!({ "command": item["command"] }));
+            if item.get("exit_code").is_some_and(|code| !code.is_null()) {
+                call.raw_output = Some(json!({ "output": item["aggregated_output"], "exit_code": item["exit_code"] }));
+            }
+        }
+        "file_change" => {
+            let changes = item["changes"].as_array().cloned().unwrap_or_default();
+            call.locations = changes
+                .iter()
+                .filter_map(|change| change["path"].as_str())
+                .map(|path| ToolCallLocation { path: path.to_string(), line: None })
+                .collect();
+            let paths: Vec<_> = call.locations.iter().map(|location| location.path.as_str()).collect();
+            call.title = format!("Edit {}", paths.join(", "));
+            call.kind = Some("edit".to_string());
+            call.raw_input = Some(Value::Array(changes));
+        }
+        "mcp_tool_call" => {
+            call.title = format!("{}/{}", field("server"), field("tool"));
+            call.kind = Some("other".to_string());
+            call.raw_input = item.get("arguments").cloned();
+            call.raw_output = item.get("result").or_else(|| item.get("error")).cloned();
+        }
+        "web_search" => {
+            call.title = field("query");
+            call.kind = Some("fetch".to_string());
+            call.raw_input = Some(json!({ "query": item["query"] }));
+        }
+        _ => return None,
+    }
+    Some(call)
+}
+
+/// The plan in a Codex to-do list item
+fn plan(item: &Value) -> Vec<PlanEntry> {
+    item["items"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|entry| PlanEntry {
+            content: entry["text"].as_str().unwrap_or_default().to_string(),
+            priority: PlanEntryPriority::default(),
+            status: if entry["completed"].as_bool().unwrap_or(false) {
+                PlanEntryStatus::Completed
+            } else {
+                PlanEntryStatus::Pending
+            },
+        })
+        .collect()
+}
+
 /// Strip ANSI escape codes from a string
 fn strip_ansi_codes(s: &str) -> String {
     let re = regex::Regex::new(r"\x1b\[[0-9;]*[a-zA-Z]|\x1b\].*?\x07|\r").unwrap();
@@ -168,8 +327,8 @@ mod tests {
         let agent = CodexAgent::new();
         assert_eq!(agent.name(), "codex");
         let args = agent.chat_args();
-        assert!(args.contains(&"-q".to_string()));
-        assert!(args.contains(&"--approval-mode".to_string()));
+        assert!(args.contains(&"exec".to_string()));
+        assert!(args.contains(&"--sandbox".to_string()));
     }
 
     #[test]
@@ -184,7 +343,11 @@ mod tests {
     fn test_full_auto_mode() {
         let agent = CodexAgent::new().with_approval_mode(CodexApprovalMode::FullAuto);
         let args = agent.chat_args();
-        assert!(args.contains(&"full-auto".to_string()));
+        assert!(args.contains(&"--full-auto".to_string()));
+        assert!(!args.contains(&"--sandbox".to_string()));
+
+        let agent = CodexAgent::new().with_approval_mode(CodexApprovalMode::AutoEdit);
+        assert!(agent.chat_args().contains(&"workspace-write".to_string()));
     }
 
     #[test]
@@ -194,10 +357,66 @@ mod tests {
         assert!(args.contains(&"--json".to_string()));
     }
 
+    #[test]
+    fn test_transport() {
+        assert_eq!(CodexAgent::new().transport(), AgentTransport::Cli);
+        assert_eq!(CodexAgent::new().with_json_output(true).transport(), AgentTransport::StreamingCli);
+    }
+
+    #[test]
+    fn test_json_parser() {
+        let mut parser = CodexJsonParser::new();
+        let lines = [
+            r#"{"type":"thread.started","thread_id":"th_1"}"#,
+            r#"{"type":"item.completed","item":{"id":"item_0","type":"reasoning","text":"Listing files"}}"#,
+            r#"{"type":"item.started","item":{"id":"item_1","type":"command_execution","command":"ls","aggregated_output":"","exit_code":null,"status":"in_progress"}}"#,
+            r#"{"type":"item.completed","item":{"id":"item_1","type":"command_execution","command":"ls","aggregated_output":"Cargo.toml\n","exit_code":0,"status":"completed"}}"#,
+            r#"{"type":"item.updated","item":{"id":"item_2","type":"todo_list","items":[{"text":"List files","completed":true},{"text":"Summarize","completed":false}]}}"#,
+            r#"{"type":"item.completed","item":{"id":"item_3","type":"agent_message","text":"One file."}}"#,
+            r#"{"type":"item.completed","item":{"id":"item_4","type":"agent_message","text":"Done."}}"#,
+            r#"{"type":"turn.completed","usage":{"input_tokens":200,"cached_input_tokens":50,"output_tokens":20}}"#,
+        ];
+        let events: Vec<_> = lines.iter().flat_map(|line| parser.parse_line(line)).collect();
+
+        assert_eq!(events[0], SessionEvent::ThoughtChunk("Listing files\n".into()));
+        assert!(matches!(&events[1], SessionEvent::ToolCallStarted(call) if call.title == "ls"));
+        assert!(matches!(
+            &events[2],
+            SessionEvent::ToolCallUpdated(call) if call.status == ToolCallStatus::Completed && call.raw_output.is_some()
+        ));
+        assert!(matches!(&events[3], SessionEvent::Plan(plan) if plan[0].status == PlanEntryStatus::Completed));
+        assert_eq!(events[4], SessionEvent::MessageChunk("One file.".into()));
+        assert_eq!(events[5], SessionEvent::MessageChunk("\n\nDone.".into()));
+        assert!(matches!(events[6], SessionEvent::Usage(TokenUsage { total_tokens: 220, .. })));
+        assert_eq!(events[7], SessionEvent::Stopped(StopReason::EndTurn));
+
+        let failed = parser.parse_line(r#"{"type":"turn.failed","error":{"message":"stream disconnected"}}"#);
+        assert_eq!(failed, [SessionEvent::Error("stream disconnected".into())]);
+    }
+
     #[test]
     fn test_environment_vars() {
         let agent = CodexAgent::new();
         let env = agent.environment();
         assert!(env.iter().any(|(k, v)| k == "CODEX_QUIET_MODE" && v == "1"));
     }
+
+    #[test]
+    fn test_exec_json_fixture() {
+        let mut parser = CodexJsonParser::new();
+        let events: Vec<_> = include_str!("testdata/codex_exec.jsonl")
+            .lines()
+            .flat_map(|line| parser.parse_line(line))
+            .collect();
+
+        assert_eq!(events.len(), 9);
+        assert_eq!(events[0], SessionEvent::ThoughtChunk("**Searching for README files**\n".into()));
+        assert!(matches!(&events[1], SessionEvent::ToolCallStarted(call) if call.title == "bash -lc ls"));
+        assert!(matches!(&events[2], SessionEvent::ToolCallUpdated(call) if call.status == ToolCallStatus::Completed));
+        assert!(matches!(&events[4], SessionEvent::Plan(plan) if plan[0].status == PlanEntryStatus::Completed));
+        assert!(matches!(&events[5], SessionEvent::ToolCallStarted(call) if call.locations[0].path == "docs/summary.md"));
+        assert_eq!(events[6], SessionEvent::MessageChunk("Yep — there’s a `README.md` in the repository root.".into()));
+        assert!(matches!(events[7], SessionEvent::Usage(TokenUsage { input_tokens: 24763, output_tokens: 122, .. })));
+        assert_eq!(events[8], SessionEvent::Stopped(StopReason::EndTurn));
+    }
 }
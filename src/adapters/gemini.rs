@@ -9,9 +9,16 @@
 //! - Interactive: `gemini`
 //! - Non-interactive: `gemini -p "prompt"`
 //! - With model: `gemini -m gemini-2.5-flash -p "prompt"`
+//! - ACP: `gemini --experimental-acp`
 
-use std::time::Duration;
-use crate::domain::Agent;
+use std::collections::HashMap;
+use std::path::Path;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::domain::{
+    Agent, AgentTransport, OutputParser, SessionEvent, StopReason, TokenUsage, ToolCall, ToolCallStatus,
+};
 
 /// Output format for Gemini CLI responses
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -37,14 +44,17 @@ impl GeminiOutputFormat {
 
 /// Google Gemini CLI agent implementation
 ///
-/// Note: Gemini CLI may support MCP servers but doesn't appear to use ACP protocol.
-/// This agent uses the non-interactive prompt mode (-p) for programmatic access.
+/// By default this agent runs the non-interactive prompt mode (-p) once per
+/// prompt. With [`GeminiAgent::with_acp`] it speaks ACP instead, which adds
+/// streaming, tool calls and multi-turn sessions.
 #[derive(Debug, Clone)]
 pub struct GeminiAgent {
     cli_path: String,
     model: Option<String>,
     output_format: GeminiOutputFormat,
     include_directories: Vec<String>,
+    acp: bool,
+    auth_method: Option<String>,
 }
 
 impl GeminiAgent {
@@ -56,6 +66,8 @@ impl GeminiAgent {
             model: std::env::var("GEMINI_MODEL").ok(),
             output_format: GeminiOutputFormat::default(),
             include_directories: vec![],
+            acp: false,
+            auth_method: None,
         }
     }
 
@@ -66,6 +78,8 @@ impl GeminiAgent {
             model: None,
             output_format: GeminiOutputFormat::default(),
             include_directories: vec![],
+            acp: false,
+            auth_method: None,
         }
     }
 
@@ -92,6 +106,39 @@ impl GeminiAgent {
         self.include_directories.push(dir.into());
         self
     }
+
+    /// Run Gemini as an ACP agent (`--experimental-acp`) instead of once per prompt
+    pub fn with_acp(mut self, enabled: bool) -> Self {
+        self.acp = enabled;
+        self
+    }
+
+    /// Authenticate ACP sessions with this method (e.g., "gemini-api-key")
+    ///
+    /// By default an API key or Vertex AI setup in the environment is used,
+    /// or else the cached Google login if there is one.
+    pub fn with_auth_method(mut self, method: impl Into<String>) -> Self {
+        self.auth_method = Some(method.into());
+        self
+    }
+
+    /// Model and context directory arguments, shared by both modes
+    fn context_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if let Some(ref model) = self.model {
+            args.push("-m".to_string());
+            args.push(model.clone());
+        }
+
+        // Included directories become part of every session's workspace context
+        if !self.include_directories.is_empty() {
+            args.push("--include-directories".to_string());
+            args.push(self.include_directories.join(","));
+        }
+
+        args
+    }
 }
 
 impl Default for GeminiAgent {
@@ -111,8 +158,25 @@ impl Agent for GeminiAgent {
     }
 
     fn acp_args(&self) -> Vec<String> {
-        // Gemini CLI doesn't support ACP, return chat args
-        self.chat_args()
+        if !self.acp {
+            // One-shot mode doesn't speak ACP, return chat args
+            return self.chat_args();
+        }
+
+        let mut args = vec!["--experimental-acp".to_string()];
+        args.extend(self.context_args());
+        args
+    }
+
+    fn transport(&self) -> AgentTransport {
+        if self.acp {
+            return AgentTransport::Acp;
+        }
+
+        match self.output_format {
+            GeminiOutputFormat::StreamJson => AgentTransport::StreamingCli,
+            GeminiOutputFormat::Text | GeminiOutputFormat::Json => AgentTransport::Cli,
+        }
     }
 
     fn chat_args(&self) -> Vec<String> {
@@ -120,23 +184,13 @@ impl Agent for GeminiAgent {
             "-p".to_string(), // Non-interactive prompt mode
         ];
 
-        if let Some(ref model) = self.model {
-            args.push("-m".to_string());
-            args.push(model.clone());
-        }
-
         // Add output format if not default text
         if self.output_format != GeminiOutputFormat::Text {
             args.push("--output-format".to_string());
             args.push(self.output_format.as_str().to_string());
         }
 
-        // Add include directories
-        if !self.include_directories.is_empty() {
-            args.push("--include-directories".to_string());
-            args.push(self.include_directories.join(","));
-        }
-
+        args.extend(self.context_args());
         args
     }
 
@@ -144,12 +198,16 @@ impl Agent for GeminiAgent {
         false
     }
 
-    fn session_init_delay(&self) -> Duration {
-        Duration::ZERO
-    }
+    fn auth_methods(&self) -> Vec<String> {
+        if let Some(ref method) = self.auth_method {
+            return vec![method.clone()];
+        }
 
-    fn post_prompt_delay(&self) -> Duration {
-        Duration::from_millis(100)
+        default_auth_methods(
+            std::env::var_os("GEMINI_API_KEY").is_some(),
+            std::env::var_os("GOOGLE_GENAI_USE_VERTEXAI").is_some(),
+            has_cached_login(),
+        )
     }
 
     fn process_response(&self, response: &str) -> String {
@@ -157,11 +215,133 @@ impl Agent for GeminiAgent {
         strip_ansi_codes(response)
     }
 
+    fn output_parser(&self) -> Option<Box<dyn OutputParser>> {
+        match self.output_format {
+            GeminiOutputFormat::StreamJson => Some(Box::new(GeminiStreamParser::new())),
+            GeminiOutputFormat::Text | GeminiOutputFormat::Json => None,
+        }
+    }
+
     fn environment(&self) -> Vec<(String, String)> {
         vec![]
     }
 }
 
+/// Parser for Gemini's `--output-format stream-json` events
+///
+/// Handles `message`, `tool_use`, `tool_result`, `error` and `result` events;
+/// other events and lines that aren't JSON are skipped.
+#[derive(Debug, Default)]
+pub struct GeminiStreamParser {
+    /// Tool calls waiting for their result, by tool ID
+    tool_calls: HashMap<String, ToolCall>,
+}
+
+impl GeminiStreamParser {
+    /// Create a parser for one prompt
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl OutputParser for GeminiStreamParser {
+    fn parse_line(&mut self, line: &str) -> Vec<SessionEvent> {
+        let Ok(event) = serde_json::from_str::<Value>(line) else {
+            return vec![];
+        };
+        let field = |name: &str| event[name].as_str().unwrap_or_default().to_string();
+
+        match event["type"].as_str().unwrap_or_default() {
+            "message" if event["role"] == "assistant" => vec![SessionEvent::MessageChunk(field("content"))],
+            "tool_use" => {
+                let name = field("tool_name");
+                let call = ToolCall {
+                    id: field("tool_id"),
+                    kind: tool_kind(&name).map(String::from),
+                    title: name,
+                    status: ToolCallStatus::InProgress,
+                    raw_input: event.get("parameters").cloned(),
+                    ..Default::default()
+                };
+                self.tool_calls.insert(call.id.clone(), call.clone());
+                vec![SessionEvent::ToolCallStarted(call)]
+            }
+            "tool_result" => {
+                let id = field("tool_id");
+                let mut call = self.tool_calls.remove(&id).unwrap_or_else(|| ToolCall { id, ..Default::default() });
+                call.status = if event["status"] == "success" {
+                    ToolCallStatus::Completed
+                } else {
+                    ToolCallStatus::Failed
+                };
+                call.raw_output = event.get("output").or_else(|| event.get("error")).cloned();
+                vec![SessionEvent::ToolCallUpdated(call)]
+            }
+            "error" if event["severity"] == "warning" => {
+                warn!("[Gemini] {}", field("message"));
+                vec![]
+            }
+            "error" => vec![SessionEvent::Error(field("message"))],
+            "result" => {
+                let mut events = Vec::new();
+                if let Some(stats) = event.get("stats") {
+                    let count = |name: &str| stats[name].as_u64().unwrap_or(0);
+                    let (input_tokens, output_tokens) = (count("input_tokens"), count("output_tokens"));
+                    events.push(SessionEvent::Usage(TokenUsage {
+                        input_tokens,
+                        output_tokens,
+                        total_tokens: stats["total_tokens"].as_u64().unwrap_or(input_tokens + output_tokens),
+                    }));
+                }
+                events.push(if event["status"] == "error" {
+                    SessionEvent::Error(event["error"]["message"].as_str().unwrap_or("Gemini failed").to_string())
+                } else {
+                    SessionEvent::Stopped(StopReason::EndTurn)
+                });
+                events
+            }
+            _ => vec![],
+        }
+    }
+}
+
+/// ACP tool kind of a Gemini built-in tool
+fn tool_kind(tool_name: &str) -> Option<&'static str> {
+    match tool_name {
+        "read_file" | "read_many_files" | "list_directory" => Some("read"),
+        "write_file" | "replace" => Some("edit"),
+        "glob" | "search_file_content" => Some("search"),
+        "run_shell_command" => Some("execute"),
+        "web_fetch" | "google_web_search" => Some("fetch"),
+        _ => None,
+    }
+}
+
+/// Authentication methods to try when none was picked explicitly
+///
+/// Credentials in the environment win. The Google login is only used when it
+/// is cached, since logging in would wait for a browser that a server doesn't
+/// have; with no credentials at all the agent reports it can't authenticate.
+fn default_auth_methods(api_key: bool, vertex_ai: bool, cached_login: bool) -> Vec<String> {
+    let mut methods = Vec::new();
+    if api_key {
+        methods.push("gemini-api-key".to_string());
+    }
+    if vertex_ai {
+        methods.push("vertex-ai".to_string());
+    }
+    if methods.is_empty() && cached_login {
+        methods.push("oauth-personal".to_string());
+    }
+    methods
+}
+
+/// Whether Gemini CLI has a cached Google login (`~/.gemini/oauth_creds.json`)
+fn has_cached_login() -> bool {
+    std::env::var_os("HOME")
+        .is_some_and(|home| Path::new(&home).join(".gemini").join("oauth_creds.json").is_file())
+}
+
 /// Strip ANSI escape codes from a string
 fn strip_ansi_codes(s: &str) -> String {
     let re = regex::Regex::new(r"\x1b\[[0-9;]*[a-zA-Z]|\x1b\].*?\x07|\r").unwrap();
@@ -210,5 +390,58 @@ mod tests {
     fn test_no_acp() {
         let agent = GeminiAgent::new();
         assert!(!agent.requires_mcp_servers());
+        assert_eq!(agent.transport(), AgentTransport::Cli);
+
+        let streaming = GeminiAgent::new().with_output_format(GeminiOutputFormat::StreamJson);
+        assert_eq!(streaming.transport(), AgentTransport::StreamingCli);
+    }
+
+    #[test]
+    fn test_stream_json_parser() {
+        let mut parser = GeminiStreamParser::new();
+        let lines = [
+            r#"{"type":"init","session_id":"s1","model":"gemini-2.5-pro"}"#,
+            r#"{"type":"message","role":"user","content":"List files"}"#,
+            r#"{"type":"tool_use","tool_name":"list_directory","tool_id":"t1","parameters":{"path":"."}}"#,
+            r#"{"type":"tool_result","tool_id":"t1","status":"success","output":"Cargo.toml"}"#,
+            r#"{"type":"message","role":"assistant","content":"There is ","delta":true}"#,
+            r#"{"type":"message","role":"assistant","content":"one file.","delta":true}"#,
+            "Loaded cached credentials.",
+            r#"{"type":"result","status":"success","stats":{"input_tokens":120,"output_tokens":8,"total_tokens":128}}"#,
+        ];
+        let events: Vec<_> = lines.iter().flat_map(|line| parser.parse_line(line)).collect();
+
+        assert!(matches!(&events[0], SessionEvent::ToolCallStarted(call) if call.kind.as_deref() == Some("read")));
+        assert!(matches!(
+            &events[1],
+            SessionEvent::ToolCallUpdated(call) if call.status == ToolCallStatus::Completed && call.title == "list_directory"
+        ));
+        assert_eq!(events[2], SessionEvent::MessageChunk("There is ".into()));
+        assert_eq!(events[3], SessionEvent::MessageChunk("one file.".into()));
+        assert!(matches!(events[4], SessionEvent::Usage(TokenUsage { total_tokens: 128, .. })));
+        assert_eq!(events[5], SessionEvent::Stopped(StopReason::EndTurn));
+        assert_eq!(events.len(), 6);
+
+        let failed = parser.parse_line(r#"{"type":"result","status":"error","error":{"message":"Quota exceeded"}}"#);
+        assert_eq!(failed, [SessionEvent::Error("Quota exceeded".into())]);
+    }
+
+    #[test]
+    fn test_acp_mode() {
+        let agent = GeminiAgent::with_cli_path("gemini")
+            .with_model("gemini-2.5-pro")
+            .include_directory("../docs")
+            .with_acp(true);
+        assert_eq!(agent.transport(), AgentTransport::Acp);
+        assert_eq!(
+            agent.acp_args(),
+            ["--experimental-acp", "-m", "gemini-2.5-pro", "--include-directories", "../docs"]
+        );
+
+        let agent = agent.with_auth_method("vertex-ai");
+        assert_eq!(agent.auth_methods(), ["vertex-ai"]);
+        assert_eq!(default_auth_methods(true, true, true), ["gemini-api-key", "vertex-ai"]);
+        assert_eq!(default_auth_methods(false, false, true), ["oauth-personal"]);
+        assert!(default_auth_methods(false, false, false).is_empty());
     }
 }
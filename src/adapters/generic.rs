@@ -0,0 +1,279 @@
+//! Generic Agent Adapter
+//!
+//! An agent described by a config file instead of code, so new CLIs
+//! (claude-code-acp, goose, ...) can be served without a rebuild.
+//!
+//! ```toml
+//! name = "my-agent"
+//! cli_path = "my-agent-cli"
+//! acp_args = ["acp"]
+//! chat_args = ["chat", "--no-interactive"]
+//! mode_args = ["--profile", "{mode}"]
+//! modes = ["review"]
+//! session_init_delay_ms = 1000
+//! strip_patterns = ['\x1b\[[0-9;]*[a-zA-Z]']
+//!
+//! [env]
+//! MY_AGENT_LOG = "error"
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{Agent, AgentTransport};
+use crate::error::{Error, Result};
+
+/// Placeholder replaced by the mode name in `mode_args`
+const MODE_PLACEHOLDER: &str = "{mode}";
+
+/// Declarative description of an agent, as read from TOML or JSON
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GenericAgentSpec {
+    /// Agent name (also the model name it is served under)
+    pub name: String,
+    /// Path to the CLI executable
+    pub cli_path: String,
+    /// CLI arguments for starting ACP mode
+    pub acp_args: Vec<String>,
+    /// CLI arguments for non-interactive chat mode
+    pub chat_args: Vec<String>,
+    /// How prompts reach the agent
+    pub transport: AgentTransport,
+    /// Arguments selecting a mode, with `{mode}` standing for its name
+    pub mode_args: Vec<String>,
+    /// Modes to serve as `name/mode` models
+    pub modes: Vec<String>,
+    /// Extra environment variables
+    pub env: BTreeMap<String, String>,
+    /// Whether the agent requires mcpServers in session/new requests
+    pub requires_mcp_servers: bool,
+    /// Authentication methods to use, in order of preference
+    pub auth_methods: Vec<String>,
+    /// Longest wait for the first session to be ready, in milliseconds
+    pub session_init_delay_ms: u64,
+    /// Extra delay after each prompt, in milliseconds
+    pub post_prompt_delay_ms: u64,
+    /// Regular expressions removed from response text
+    pub strip_patterns: Vec<String>,
+}
+
+impl Default for GenericAgentSpec {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            cli_path: String::new(),
+            acp_args: Vec::new(),
+            chat_args: Vec::new(),
+            transport: AgentTransport::default(),
+            mode_args: vec!["--agent".to_string(), MODE_PLACEHOLDER.to_string()],
+            modes: Vec::new(),
+            env: BTreeMap::new(),
+            requires_mcp_servers: true,
+            auth_methods: Vec::new(),
+            session_init_delay_ms: 0,
+            post_prompt_delay_ms: 0,
+            strip_patterns: Vec::new(),
+        }
+    }
+}
+
+/// Agent defined by a `GenericAgentSpec`
+#[derive(Debug, Clone)]
+pub struct GenericAgent {
+    spec: GenericAgentSpec,
+    strip_patterns: Vec<Regex>,
+}
+
+impl GenericAgent {
+    /// Create an agent from a spec, checking it is usable
+    pub fn from_spec(spec: GenericAgentSpec) -> Result<Self> {
+        if spec.name.is_empty() || spec.name.contains('/') {
+            return Err(Error::config(format!("Invalid agent name '{}'", spec.name)));
+        }
+        if spec.cli_path.is_empty() {
+            return Err(Error::config(format!("Agent '{}' has no cli_path", spec.name)));
+        }
+
+        let strip_patterns = spec
+            .strip_patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|e| Error::config(format!("Invalid pattern '{}': {}", pattern, e)))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { spec, strip_patterns })
+    }
+
+    /// Parse an agent from TOML
+    pub fn from_toml(source: &str) -> Result<Self> {
+        let spec = toml::from_str(source).map_err(|e| Error::config(format!("Invalid agent config: {}", e)))?;
+        Self::from_spec(spec)
+    }
+
+    /// Parse an agent from JSON
+    pub fn from_json(source: &str) -> Result<Self> {
+        let spec = serde_json::from_str(source).map_err(|e| Error::config(format!("Invalid agent config: {}", e)))?;
+        Self::from_spec(spec)
+    }
+
+    /// Load an agent from a `.toml` or `.json` file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| Error::config(format!("{}: {}", path.display(), e)))?;
+
+        let agent = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml(&source),
+            Some("json") => Self::from_json(&source),
+            _ => return Err(Error::config(format!("{}: expected a .toml or .json file", path.display()))),
+        };
+        agent.map_err(|e| Error::config(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Load every `.toml` and `.json` agent in a directory, in file name order
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Vec<Self>> {
+        let dir = dir.as_ref();
+        let entries = std::fs::read_dir(dir).map_err(|e| Error::config(format!("{}: {}", dir.display(), e)))?;
+
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| matches!(path.extension().and_then(|ext| ext.to_str()), Some("toml" | "json")))
+            .collect();
+        paths.sort();
+        paths.iter().map(Self::from_file).collect()
+    }
+
+    /// The spec this agent was created from
+    pub fn spec(&self) -> &GenericAgentSpec {
+        &self.spec
+    }
+
+    /// Modes to serve as `name/mode` models
+    pub fn modes(&self) -> &[String] {
+        &self.spec.modes
+    }
+}
+
+#[async_trait::async_trait]
+impl Agent for GenericAgent {
+    fn name(&self) -> &str {
+        &self.spec.name
+    }
+
+    fn cli_path(&self) -> &str {
+        &self.spec.cli_path
+    }
+
+    fn acp_args(&self) -> Vec<String> {
+        self.spec.acp_args.clone()
+    }
+
+    fn chat_args(&self) -> Vec<String> {
+        self.spec.chat_args.clone()
+    }
+
+    fn mode_args(&self, mode: &str) -> Vec<String> {
+        self.spec
+            .mode_args
+            .iter()
+            .map(|arg| arg.replace(MODE_PLACEHOLDER, mode))
+            .collect()
+    }
+
+    fn transport(&self) -> AgentTransport {
+        self.spec.transport
+    }
+
+    fn requires_mcp_servers(&self) -> bool {
+        self.spec.requires_mcp_servers
+    }
+
+    fn auth_methods(&self) -> Vec<String> {
+        self.spec.auth_methods.clone()
+    }
+
+    fn session_init_delay(&self) -> Duration {
+        Duration::from_millis(self.spec.session_init_delay_ms)
+    }
+
+    fn post_prompt_delay(&self) -> Duration {
+        Duration::from_millis(self.spec.post_prompt_delay_ms)
+    }
+
+    fn process_response(&self, response: &str) -> String {
+        self.strip_patterns
+            .iter()
+            .fold(response.to_string(), |text, pattern| pattern.replace_all(&text, "").into_owned())
+    }
+
+    fn environment(&self) -> Vec<(String, String)> {
+        self.spec.env.iter().map(|(key, value)| (key.clone(), value.clone())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml() {
+        let agent = GenericAgent::from_toml(
+            r#"
+            name = "claude"
+            cli_path = "claude-code-acp"
+            mode_args = ["--permission-mode={mode}"]
+            modes = ["plan"]
+            session_init_delay_ms = 500
+            strip_patterns = ['\x1b\[[0-9;]*m']
+
+            [env]
+            ANTHROPIC_LOG = "error"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(agent.name(), "claude");
+        assert_eq!(agent.transport(), AgentTransport::Acp);
+        assert_eq!(agent.mode_args("plan"), ["--permission-mode=plan"]);
+        assert_eq!(agent.modes(), ["plan"]);
+        assert_eq!(agent.session_init_delay(), Duration::from_millis(500));
+        assert_eq!(agent.environment(), [("ANTHROPIC_LOG".to_string(), "error".to_string())]);
+        assert_eq!(agent.process_response("\x1b[1mHi\x1b[0m"), "Hi");
+        assert!(agent.requires_mcp_servers());
+    }
+
+    #[test]
+    fn test_from_json() {
+        let agent = GenericAgent::from_json(
+            r#"{"name": "goose", "cli_path": "goose", "chat_args": ["run", "-i", "-"], "transport": "streaming_cli"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(agent.transport(), AgentTransport::StreamingCli);
+        assert_eq!(agent.chat_args(), ["run", "-i", "-"]);
+        assert_eq!(agent.mode_args("dev"), ["--agent", "dev"]);
+    }
+
+    #[test]
+    fn test_invalid_specs() {
+        let invalid = [
+            r#"cli_path = "goose""#,
+            r#"name = "a/b"
+               cli_path = "goose""#,
+            r#"name = "goose""#,
+            r#"name = "goose"
+               cli_path = "goose"
+               strip_patterns = ["("]"#,
+            r#"name = "goose"
+               cli_path = "goose"
+               transport = "carrier_pigeon""#,
+        ];
+        for source in invalid {
+            assert!(matches!(GenericAgent::from_toml(source), Err(Error::Config(_))), "{}", source);
+        }
+    }
+}
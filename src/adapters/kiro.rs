@@ -76,15 +76,10 @@ impl Agent for KiroAgent {
     }
 
     fn session_init_delay(&self) -> Duration {
-        // Kiro needs time to initialize MCP servers
+        // Kiro announces its commands once its MCP servers are up
         Duration::from_secs(2)
     }
 
-    fn post_prompt_delay(&self) -> Duration {
-        // Small delay to ensure all messages are received
-        Duration::from_millis(500)
-    }
-
     fn process_response(&self, response: &str) -> String {
         // Strip ANSI escape codes from Kiro output
         strip_ansi_codes(response)
@@ -3,13 +3,14 @@
 //! A mock implementation of the Agent trait for testing.
 
 use std::time::Duration;
-use crate::domain::Agent;
+use crate::domain::{Agent, AgentTransport};
 
 /// Mock agent for testing purposes
 #[derive(Debug, Clone)]
 pub struct MockAgent {
     name: String,
     response: String,
+    transport: AgentTransport,
 }
 
 impl MockAgent {
@@ -18,6 +19,7 @@ impl MockAgent {
         Self {
             name: "mock".to_string(),
             response: "Mock response".to_string(),
+            transport: AgentTransport::default(),
         }
     }
 
@@ -27,6 +29,12 @@ impl MockAgent {
         self
     }
 
+    /// Set the transport (`Cli` answers every prompt with the response via `echo`)
+    pub fn with_transport(mut self, transport: AgentTransport) -> Self {
+        self.transport = transport;
+        self
+    }
+
     /// Set a custom name
     pub fn with_name(mut self, name: impl Into<String>) -> Self {
         self.name = name.into();
@@ -58,6 +66,10 @@ impl Agent for MockAgent {
         vec![self.response.clone()]
     }
 
+    fn transport(&self) -> AgentTransport {
+        self.transport
+    }
+
     fn requires_mcp_servers(&self) -> bool {
         false
     }
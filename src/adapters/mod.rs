@@ -7,16 +7,19 @@
 //! | Agent | CLI | ACP Support | Notes |
 //! |-------|-----|-------------|-------|
 //! | KiroAgent | kiro-cli | Yes | Full ACP protocol support |
-//! | CodexAgent | codex | No | Uses quiet mode (-q) |
-//! | GeminiAgent | gemini | No | Uses prompt mode (-p) |
+//! | CodexAgent | codex | No | Runs `codex exec`, parses `--json` events |
+//! | GeminiAgent | gemini | Optional | Prompt mode (-p) or `--experimental-acp` |
+//! | GenericAgent | any | Configurable | Defined in a TOML/JSON file |
 //! | MockAgent | - | No | For testing only |
 
 mod codex;
 mod gemini;
+mod generic;
 mod kiro;
 mod mock;
 
-pub use codex::{CodexAgent, CodexApprovalMode};
-pub use gemini::{GeminiAgent, GeminiOutputFormat};
+pub use codex::{CodexAgent, CodexApprovalMode, CodexJsonParser};
+pub use gemini::{GeminiAgent, GeminiOutputFormat, GeminiStreamParser};
+pub use generic::{GenericAgent, GenericAgentSpec};
 pub use kiro::KiroAgent;
 pub use mock::MockAgent;
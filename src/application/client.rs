@@ -2,32 +2,87 @@
 //!
 //! Main application service for interacting with agents via ACP.
 
-use std::sync::Arc;
-use tokio::task::LocalSet;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 use tracing::{info, error, warn};
 
-use crate::domain::{Agent, AgentConfig, Message, Session};
+use crate::domain::{
+    Agent, AgentCapabilities, AgentConfig, AgentInfo, AgentResponse, McpServer, Message, Prompt,
+    Role, Session, SessionEvent, StopReason,
+};
 use crate::error::{Error, Result};
-use crate::infrastructure::acp::{AcpConnection, ResponseCollector};
-use super::SessionService;
+use crate::infrastructure::acp::{AcpPool, LaunchSpec, PooledConnection, ResponseCollector};
+use crate::infrastructure::cli::CliRunner;
+use super::{SessionEventStream, SessionService};
 
 /// Generic ACP client that works with any Agent implementation
+///
+/// Prompts to ACP agents run on warm agent processes from a per-client
+/// [`AcpPool`]. Each process is driven by its own worker thread, so the client
+/// is `Send + Sync` and its futures can be awaited (or spawned) from any Tokio
+/// runtime. Agents with a CLI transport get one process per prompt instead.
 pub struct AcpClient<A: Agent> {
     agent: A,
     config: AgentConfig,
     sessions: SessionService,
+    pool: AcpPool,
+    /// Runs the agent's chat command (CLI agents, and ACP agents that answered nothing)
+    cli: CliRunner,
+    /// Turns currently running (turn ID and cancellation token), by session ID
+    active_turns: Mutex<HashMap<String, (u64, CancellationToken)>>,
+    /// ID of the next turn
+    next_turn: AtomicU64,
+    /// Agent identity and capabilities from the last connection used
+    profile: Mutex<Option<(AgentInfo, AgentCapabilities)>>,
+}
+
+/// Unregisters a running turn when it finishes
+struct ActiveTurn<'a> {
+    turns: &'a Mutex<HashMap<String, (u64, CancellationToken)>>,
+    session_id: &'a str,
+    id: u64,
+}
+
+impl Drop for ActiveTurn<'_> {
+    fn drop(&mut self) {
+        let mut turns = self.turns.lock().unwrap();
+        if turns.get(self.session_id).is_some_and(|(id, _)| *id == self.id) {
+            turns.remove(self.session_id);
+        }
+    }
 }
 
 impl<A: Agent> AcpClient<A> {
     /// Create a new ACP client with the given agent and configuration
     pub fn new(agent: A, config: AgentConfig) -> Self {
+        let pool = AcpPool::new(
+            LaunchSpec::new(&agent, &config),
+            config.pool_size,
+            config.idle_timeout,
+        );
+        let cli = CliRunner::new(&agent, &config);
+
         Self {
             agent,
             config,
             sessions: SessionService::new(),
+            pool,
+            cli,
+            active_turns: Mutex::new(HashMap::new()),
+            next_turn: AtomicU64::new(0),
+            profile: Mutex::new(None),
         }
     }
 
+    /// Keep sessions in `sessions`, e.g., to share them between clients
+    pub fn with_sessions(mut self, sessions: SessionService) -> Self {
+        self.sessions = sessions;
+        self
+    }
+
     /// Get the agent
     pub fn agent(&self) -> &A {
         &self.agent
@@ -43,27 +98,185 @@ impl<A: Agent> AcpClient<A> {
         &self.sessions
     }
 
+    /// Get the agent process pool
+    pub fn pool(&self) -> &AcpPool {
+        &self.pool
+    }
+
+    /// Agent identity reported during `initialize`
+    ///
+    /// Starts an agent process if none has been started yet.
+    pub async fn info(&self) -> Result<AgentInfo> {
+        Ok(self.profile().await?.0)
+    }
+
+    /// Capabilities advertised by the agent
+    ///
+    /// Starts an agent process if none has been started yet. Modes and models
+    /// are filled in once the agent has created a session.
+    pub async fn capabilities(&self) -> Result<AgentCapabilities> {
+        Ok(self.profile().await?.1)
+    }
+
+    /// Cached agent identity and capabilities, refreshed until session details are known
+    async fn profile(&self) -> Result<(AgentInfo, AgentCapabilities)> {
+        // CLI agents have no handshake, so there is nothing to ask them
+        if self.agent.transport().is_cli() {
+            let info = AgentInfo { name: self.agent.name().to_string(), ..Default::default() };
+            return Ok((info, AgentCapabilities::default()));
+        }
+
+        if let Some(profile) = self.profile.lock().unwrap().clone() {
+            let caps = &profile.1;
+            if caps.current_mode.is_some() || caps.current_model.is_some() {
+                return Ok(profile);
+            }
+        }
+
+        self.checkout(None, &CancellationToken::new()).await?;
+        self.profile
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or(Error::NotConnected)
+    }
+
     /// Create a new session
     pub async fn create_session(&self, system_prompt: Option<String>) -> Session {
         self.sessions.create(system_prompt).await
     }
 
+    /// Create a new session with MCP servers on top of the configured ones
+    ///
+    /// The servers are passed to the agent in `session/new` (and `session/load`).
+    pub async fn create_session_with_mcp_servers(
+        &self,
+        system_prompt: Option<String>,
+        mcp_servers: Vec<McpServer>,
+    ) -> Session {
+        self.sessions.create_with_mcp_servers(system_prompt, mcp_servers).await
+    }
+
+    /// Delete a session and release its ACP session
+    pub async fn delete_session(&self, session_id: &str) -> Result<Session> {
+        let session = self.sessions.delete(session_id).await?;
+        if let Some(ref acp_session_id) = session.acp_session_id {
+            self.pool.forget(acp_session_id);
+        }
+        Ok(session)
+    }
+
     /// Send a chat message in a session and get a response
-    pub async fn chat(&self, session_id: &str, content: &str) -> Result<String> {
-        // Get the session
-        let mut session = self.sessions.get(session_id).await?;
+    ///
+    /// Each session is bound to a live ACP session, so follow-up turns only
+    /// send the new message. Lost ACP sessions are restored with `session/load`
+    /// when the agent supports it; otherwise the full history is replayed.
+    pub async fn chat(&self, session_id: &str, content: impl Into<Prompt>) -> Result<AgentResponse> {
+        self.chat_cancellable(session_id, content, CancellationToken::new()).await
+    }
 
-        // Add user message
-        session.add_user_message(content);
+    /// Send a chat message that stops early when `cancel` is cancelled
+    ///
+    /// The turn can also be cancelled with [`cancel`](Self::cancel). A
+    /// cancelled turn returns (and records) the partial answer, or
+    /// [`Error::Cancelled`] if there was none.
+    pub async fn chat_cancellable(
+        &self,
+        session_id: &str,
+        content: impl Into<Prompt>,
+        cancel: CancellationToken,
+    ) -> Result<AgentResponse> {
+        self.run_chat(session_id, content.into(), None, cancel).await
+    }
+
+    /// Send a chat message, forwarding the agent's progress to `events`
+    ///
+    /// Works like [`chat_cancellable`](Self::chat_cancellable); the session's
+    /// plan is kept up to date while the agent works.
+    pub async fn chat_streaming(
+        &self,
+        session_id: &str,
+        content: impl Into<Prompt>,
+        events: mpsc::UnboundedSender<SessionEvent>,
+        cancel: CancellationToken,
+    ) -> Result<AgentResponse> {
+        self.run_chat(session_id, content.into(), Some(events), cancel).await
+    }
 
-        // Build prompt from history
-        let prompt = session.build_prompt();
+    /// Send a chat message and watch the agent work on it
+    ///
+    /// Like [`prompt_stream`](Self::prompt_stream) but for a session turn;
+    /// dropping the stream cancels the turn.
+    pub fn chat_stream(&self, session_id: &str, content: impl Into<Prompt>) -> SessionEventStream<'_> {
+        let (events_tx, events_rx) = mpsc::unbounded_channel();
+        let cancel = CancellationToken::new();
+        let cancel_on_drop = cancel.clone().drop_guard();
+        let session_id = session_id.to_string();
+        let content = content.into();
+
+        let run = async move { self.chat_streaming(&session_id, content, events_tx, cancel).await };
+        SessionEventStream::new(events_rx, Box::pin(run), cancel_on_drop)
+    }
 
-        // Send and get response
-        let response = self.send_prompt(&prompt).await?;
+    /// Run a session turn, recording both messages and the agent's plan
+    async fn run_chat(
+        &self,
+        session_id: &str,
+        content: Prompt,
+        events: Option<mpsc::UnboundedSender<SessionEvent>>,
+        cancel: CancellationToken,
+    ) -> Result<AgentResponse> {
+        // Register the turn so it can be cancelled by session ID; one turn at a time
+        let cancel = cancel.child_token();
+        let _turn = self.begin_turn(session_id, &cancel)?;
 
-        // Add assistant response
-        session.add_assistant_message(&response);
+        // Get the session
+        let mut session = self.sessions.get(session_id).await?;
+
+        // Add user message
+        session.add_message(Message::from_prompt(Role::User, &content));
+
+        // Send and get response, publishing plan changes as they arrive
+        let (updates_tx, mut updates_rx) = mpsc::unbounded_channel();
+        let collector = Arc::new(ResponseCollector::with_event_sender(updates_tx));
+        let result = {
+            let turn = self.run_session_turn(&mut session, &content, collector.clone(), &cancel);
+            tokio::pin!(turn);
+            loop {
+                tokio::select! {
+                    result = &mut turn => break result,
+                    Some(event) = updates_rx.recv() => {
+                        self.forward_session_event(session_id, event, events.as_ref()).await;
+                    }
+                }
+            }
+        };
+        while let Ok(event) = updates_rx.try_recv() {
+            self.forward_session_event(session_id, event, events.as_ref()).await;
+        }
+        let response = match self.finish_response(result, &session.history_prompt(), &collector).await {
+            Ok(response) => response,
+            Err(e) => {
+                // Keep the user message, the ACP session binding and any streamed plan
+                if let Ok(stored) = self.sessions.get(session_id).await {
+                    session.plan = stored.plan;
+                }
+                if let Err(save_error) = self.sessions.update(session).await {
+                    warn!("[AcpClient] Failed to save session {}: {}", session_id, save_error);
+                }
+                return Err(e);
+            }
+        };
+
+        // Add assistant response, keeping the agent's reasoning with it
+        let mut message = Message::assistant(&response.text);
+        if !response.thoughts.is_empty() {
+            message = message.with_reasoning(&response.thoughts);
+        }
+        session.add_message(message);
+        if !response.plan.is_empty() {
+            session.set_plan(response.plan.clone());
+        }
 
         // Update session
         self.sessions.update(session).await?;
@@ -71,86 +284,268 @@ impl<A: Agent> AcpClient<A> {
         Ok(response)
     }
 
+    /// Register a turn in a session, failing if one is already running
+    fn begin_turn<'a>(&'a self, session_id: &'a str, cancel: &CancellationToken) -> Result<ActiveTurn<'a>> {
+        let mut turns = self.active_turns.lock().unwrap();
+        if turns.contains_key(session_id) {
+            return Err(Error::session_busy(session_id));
+        }
+
+        let id = self.next_turn.fetch_add(1, Ordering::Relaxed);
+        turns.insert(session_id.to_string(), (id, cancel.clone()));
+        Ok(ActiveTurn { turns: &self.active_turns, session_id, id })
+    }
+
+    /// Record plan changes on the session and pass the event on
+    async fn forward_session_event(
+        &self,
+        session_id: &str,
+        event: SessionEvent,
+        events: Option<&mpsc::UnboundedSender<SessionEvent>>,
+    ) {
+        if let SessionEvent::Plan(plan) = &event {
+            if let Err(e) = self.sessions.set_plan(session_id, plan.clone()).await {
+                warn!("[AcpClient] Failed to store plan: {}", e);
+            }
+        }
+        if let Some(events) = events {
+            let _ = events.send(event);
+        }
+    }
+
+    /// Cancel the turn currently running in a session
+    ///
+    /// Returns whether a turn was running.
+    pub fn cancel(&self, session_id: &str) -> bool {
+        match self.active_turns.lock().unwrap().get(session_id) {
+            Some((_, token)) => {
+                info!("[AcpClient] Cancelling turn in session {}", session_id);
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Send a prompt and get a response
-    pub async fn send_prompt(&self, prompt: &str) -> Result<String> {
-        info!("[AcpClient] Sending prompt ({} chars) via {}", prompt.len(), self.agent.name());
+    pub async fn send_prompt(&self, prompt: impl Into<Prompt>) -> Result<AgentResponse> {
+        self.send_prompt_cancellable(prompt, CancellationToken::new()).await
+    }
 
-        let agent = &self.agent;
-        let config = self.config.clone();
-        let prompt_owned = prompt.to_string();
-        let prompt_for_fallback = prompt_owned.clone();
+    /// Send a prompt that stops early when `cancel` is cancelled
+    ///
+    /// A cancelled prompt returns the partial answer, or [`Error::Cancelled`]
+    /// if there was none.
+    pub async fn send_prompt_cancellable(
+        &self,
+        prompt: impl Into<Prompt>,
+        cancel: CancellationToken,
+    ) -> Result<AgentResponse> {
+        self.send_prompt_with_collector(&prompt.into(), Arc::new(ResponseCollector::new()), &cancel).await
+    }
 
-        let collector = Arc::new(ResponseCollector::new());
-        let collector_clone = collector.clone();
+    /// Send a prompt, forwarding each update to `events` as it arrives
+    ///
+    /// The complete (processed) response is still returned once the agent is done.
+    pub async fn send_prompt_streaming(
+        &self,
+        prompt: impl Into<Prompt>,
+        events: mpsc::UnboundedSender<SessionEvent>,
+        cancel: CancellationToken,
+    ) -> Result<AgentResponse> {
+        let collector = Arc::new(ResponseCollector::with_event_sender(events));
+        self.send_prompt_with_collector(&prompt.into(), collector, &cancel).await
+    }
 
-        // Run everything in a LocalSet since the ACP Client trait is not Send
-        let local = LocalSet::new();
+    /// Send a prompt and watch the agent work on it
+    ///
+    /// The stream yields message and thought chunks, tool calls, plan and mode
+    /// changes as they arrive, and ends with [`SessionEvent::Stopped`] or
+    /// [`SessionEvent::Error`]. Dropping the stream cancels the prompt.
+    pub fn prompt_stream(&self, prompt: impl Into<Prompt>) -> SessionEventStream<'_> {
+        let (events_tx, events_rx) = mpsc::unbounded_channel();
+        let cancel = CancellationToken::new();
+        let cancel_on_drop = cancel.clone().drop_guard();
+        let prompt = prompt.into();
+
+        let run = async move { self.send_prompt_streaming(prompt, events_tx, cancel).await };
+        SessionEventStream::new(events_rx, Box::pin(run), cancel_on_drop)
+    }
 
-        let result = local.run_until(async move {
-            AcpConnection::run_session(agent, &config, &prompt_owned, collector_clone).await
-        }).await;
+    /// Run a prompt with the agent's transport, collecting the response into `collector`
+    async fn send_prompt_with_collector(
+        &self,
+        prompt: &Prompt,
+        collector: Arc<ResponseCollector>,
+        cancel: &CancellationToken,
+    ) -> Result<AgentResponse> {
+        info!("[AcpClient] Sending prompt ({} chars) via {}", prompt.text().len(), self.agent.name());
+
+        let result = if self.agent.transport().is_cli() {
+            self.run_cli(prompt, &collector, cancel).await
+        } else {
+            self.run_pooled(prompt, collector.clone(), cancel).await
+        };
+        self.finish_response(result, prompt, &collector).await
+    }
 
+    /// Turn a collected response into the final answer
+    ///
+    /// Falls back to non-interactive chat with the text of `prompt` when an
+    /// ACP agent ended its turn without text or tool calls. Refusals and
+    /// limits keep their stop reason, and prompts with attachments aren't
+    /// re-sent as text.
+    async fn finish_response(
+        &self,
+        result: Result<()>,
+        prompt: &Prompt,
+        collector: &ResponseCollector,
+    ) -> Result<AgentResponse> {
         if let Err(e) = result {
             error!("[AcpClient] ACP session error: {}", e);
             return Err(e);
         }
 
-        let response = collector.get().await;
-        info!("[AcpClient] Response collected: {} chars", response.len());
+        let mut response = collector.response().await;
+        response.agent = self.profile.lock().unwrap().as_ref().map(|(info, _)| info.clone());
+        info!(
+            "[AcpClient] Response collected: {} chars, {} tool calls, {:?}",
+            response.text.len(),
+            response.tool_calls.len(),
+            response.stop_reason,
+        );
+
+        if response.is_cancelled() && response.text.is_empty() {
+            return Err(Error::Cancelled);
+        }
 
-        if response.is_empty() {
+        let nothing_done = response.text.is_empty()
+            && response.tool_calls.is_empty()
+            && response.stop_reason == StopReason::EndTurn;
+        if nothing_done && self.agent.transport().is_cli() {
+            return Err(Error::protocol("Empty response from agent"));
+        }
+        if nothing_done && !prompt.has_attachments() {
             // Fallback to non-interactive chat if ACP didn't return content
             warn!("[AcpClient] ACP returned empty, falling back to chat mode");
-            return self.send_prompt_fallback(&prompt_for_fallback).await;
+            let fallback = self.send_prompt_fallback(&prompt.text()).await?;
+            // Streaming consumers get the fallback answer as a single chunk
+            collector.append(&fallback).await;
+            response.text = fallback;
+            return Ok(response);
         }
 
-        // Process response (e.g., strip ANSI codes)
-        let processed = self.agent.process_response(&response);
-        Ok(processed)
+        // Process response (e.g., strip ANSI codes); a cancelled turn keeps its partial answer
+        response.text = self.agent.process_response(&response.text);
+        Ok(response)
     }
 
-    /// Fallback: Send a prompt via non-interactive chat
-    async fn send_prompt_fallback(&self, prompt: &str) -> Result<String> {
-        info!("[AcpClient] Using {} chat fallback", self.agent.name());
+    /// Run one turn of `session` in its ACP session
+    ///
+    /// `content` is sent on its own when the agent still holds the session or
+    /// can load it; otherwise a new ACP session is started with the full history.
+    async fn run_session_turn(
+        &self,
+        session: &mut Session,
+        content: &Prompt,
+        collector: Arc<ResponseCollector>,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
+        // CLI agents keep no context between prompts, so every turn replays the history
+        if self.agent.transport().is_cli() {
+            return self.run_cli(&session.history_prompt(), &collector, cancel).await;
+        }
 
-        use tokio::process::Command;
-        use tokio::io::AsyncWriteExt;
+        let conn = self.checkout(session.acp_session_id.as_deref(), cancel).await?;
+        let resume = match session.acp_session_id.as_deref() {
+            Some(id) if conn.hosts(id) => Some(id),
+            // The process hosting it is gone; ask the agent to load it from its own storage
+            Some(id) if conn.supports_load_session() => match conn.load_session(id, &session.mcp_servers).await {
+                Ok(()) => Some(id),
+                Err(e) => {
+                    warn!("[AcpClient] Failed to load ACP session {}: {}", id, e);
+                    None
+                }
+            },
+            _ => None,
+        };
+
+        let prompt = match resume {
+            Some(_) => content.clone(),
+            None => {
+                if let Some(ref lost) = session.acp_session_id {
+                    info!("[AcpClient] ACP session {} is gone, replaying history", lost);
+                }
+                session.history_prompt()
+            }
+        };
+
+        let acp_session_id = match conn.session_prompt(resume, &session.mcp_servers, &prompt, collector.clone(), cancel.clone()).await {
+            Err(Error::NotConnected) => {
+                warn!("[AcpClient] Connection {} was gone, retrying on a new one", conn.id());
+                drop(conn);
+                let prompt = session.history_prompt();
+                self.checkout(None, cancel).await?
+                    .session_prompt(None, &session.mcp_servers, &prompt, collector, cancel.clone())
+                    .await?
+            }
+            result => result?,
+        };
+
+        session.acp_session_id = Some(acp_session_id);
+        Ok(())
+    }
 
-        let mut cmd = Command::new(self.agent.cli_path());
-        for arg in self.agent.chat_args() {
-            cmd.arg(arg);
+    /// Run a prompt on a pooled connection
+    ///
+    /// A connection that died while idle is only noticed once a job is sent to
+    /// it, so that case is retried once on a fresh process.
+    async fn run_pooled(
+        &self,
+        prompt: &Prompt,
+        collector: Arc<ResponseCollector>,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
+        let conn = self.checkout(None, cancel).await?;
+        match conn.prompt(prompt, collector.clone(), cancel.clone()).await {
+            Err(Error::NotConnected) => {
+                warn!("[AcpClient] Connection {} was gone, retrying on a new one", conn.id());
+                drop(conn);
+                self.checkout(None, cancel).await?.prompt(prompt, collector, cancel.clone()).await
+            }
+            result => result,
         }
+    }
 
-        // Add agent environment
-        for (key, value) in self.agent.environment() {
-            cmd.env(key, value);
+    /// Run a prompt through the agent's chat command
+    ///
+    /// Only text reaches the CLI, so prompts with attachments are rejected.
+    async fn run_cli(&self, prompt: &Prompt, collector: &ResponseCollector, cancel: &CancellationToken) -> Result<()> {
+        if let Some(part) = prompt.attachments().next() {
+            return Err(Error::unsupported_content(part.modality()));
         }
+        self.cli.run(&prompt.text(), self.agent.output_parser(), collector, cancel).await
+    }
 
-        let mut child = cmd
-            .stdin(std::process::Stdio::piped())
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .spawn()
-            .map_err(|e| Error::spawn(e.to_string()))?;
+    /// Check out a pooled connection, giving up if `cancel` fires first
+    async fn checkout(&self, session_id: Option<&str>, cancel: &CancellationToken) -> Result<PooledConnection> {
+        let conn = tokio::select! {
+            biased;
+            _ = cancel.cancelled() => Err(Error::Cancelled),
+            conn = self.pool.checkout_for(session_id) => conn,
+        }?;
 
-        // Write prompt to stdin
-        if let Some(mut stdin) = child.stdin.take() {
-            stdin.write_all(prompt.as_bytes()).await
-                .map_err(|e| Error::connection(e.to_string()))?;
-            stdin.shutdown().await
-                .map_err(|e| Error::connection(e.to_string()))?;
-        }
+        *self.profile.lock().unwrap() = Some((conn.info().clone(), conn.capabilities()));
+        Ok(conn)
+    }
 
-        // Wait with timeout
-        let output = tokio::time::timeout(
-            self.config.timeout,
-            child.wait_with_output()
-        ).await
-        .map_err(|_| Error::Timeout)?
-        .map_err(|e| Error::connection(e.to_string()))?;
+    /// Fallback: Send a prompt via non-interactive chat
+    async fn send_prompt_fallback(&self, prompt: &str) -> Result<String> {
+        info!("[AcpClient] Using {} chat fallback", self.agent.name());
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let processed = self.agent.process_response(&stdout);
+        let collector = ResponseCollector::new();
+        self.cli.run(prompt, None, &collector, &CancellationToken::new()).await?;
+        let processed = self.agent.process_response(&collector.get().await);
 
         if processed.trim().is_empty() {
             return Err(Error::protocol("Empty response from agent"));
@@ -164,15 +559,16 @@ impl<A: Agent> AcpClient<A> {
         &self,
         messages: Vec<Message>,
         _model: Option<&str>,
-    ) -> Result<String> {
-        // Build prompt from messages
-        let prompt = messages
+    ) -> Result<AgentResponse> {
+        // Build prompt from messages, keeping their images and audio
+        let text = messages
             .iter()
             .map(|m| format!("{}: {}", m.role, m.content))
             .collect::<Vec<_>>()
             .join("\n\n");
+        let attachments = messages.iter().flat_map(Message::attachments).cloned();
 
-        self.send_prompt(&prompt).await
+        self.send_prompt(Prompt::from(text).with_parts(attachments)).await
     }
 }
 
@@ -200,4 +596,105 @@ mod tests {
         let session = client.create_session(Some("Be helpful".into())).await;
         assert_eq!(session.system_prompt, Some("Be helpful".into()));
     }
+
+    #[tokio::test]
+    async fn test_chat_unknown_session() {
+        let client = AcpClient::new(MockAgent::new(), AgentConfig::new("mock-cli"));
+
+        let result = client.chat("nonexistent", "Hello").await;
+        assert!(matches!(result, Err(Error::SessionNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_before_start() {
+        let client = AcpClient::new(MockAgent::new(), AgentConfig::new("mock-cli"));
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let result = client.send_prompt_cancellable("Hello", cancel).await;
+        assert!(matches!(result, Err(Error::Cancelled)));
+        assert!(!client.cancel("nonexistent"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_turn_keeps_session() {
+        let agent = MockAgent::new().with_transport(crate::domain::AgentTransport::Cli);
+        let client = AcpClient::new(agent, AgentConfig::new("mock-cli"));
+        let session = client.create_session(None).await;
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let result = client.chat_cancellable(&session.id, "Hello", cancel).await;
+        assert!(matches!(result, Err(Error::Cancelled)));
+        assert_eq!(client.sessions().get(&session.id).await.unwrap().messages.len(), 1);
+    }
+
+    #[test]
+    fn test_one_turn_per_session() {
+        let client = AcpClient::new(MockAgent::new(), AgentConfig::new("mock-cli"));
+        let cancel = CancellationToken::new();
+
+        let turn = client.begin_turn("s1", &cancel).unwrap();
+        assert!(matches!(client.begin_turn("s1", &cancel), Err(Error::SessionBusy(_))));
+        assert!(client.begin_turn("s2", &cancel).is_ok());
+        drop(turn);
+
+        // A finished turn only unregisters itself
+        let stale = ActiveTurn { turns: &client.active_turns, session_id: "s1", id: u64::MAX };
+        let _turn = client.begin_turn("s1", &cancel).unwrap();
+        drop(stale);
+        assert!(client.cancel("s1"));
+    }
+
+    #[tokio::test]
+    async fn test_cli_transport() {
+        let agent = MockAgent::new().with_transport(crate::domain::AgentTransport::Cli);
+        let client = AcpClient::new(agent, AgentConfig::new("mock-cli"));
+
+        let response = client.send_prompt("Hello").await.unwrap();
+        assert_eq!(response.text.trim(), "Mock response");
+
+        let session = client.create_session(None).await;
+        let response = client.chat(&session.id, "Hello").await.unwrap();
+        assert_eq!(response.text.trim(), "Mock response");
+        assert_eq!(client.sessions().get(&session.id).await.unwrap().messages.len(), 2);
+
+        // Nothing went through ACP
+        assert_eq!(client.pool().size(), 0);
+        assert!(!client.capabilities().await.unwrap().image);
+
+        let image = Prompt::from("Hi").with_part(crate::domain::ContentPart::image("aGk=", "image/png"));
+        assert!(matches!(client.send_prompt(image).await, Err(Error::UnsupportedContent(_))));
+    }
+
+    #[tokio::test]
+    async fn test_empty_refusal_is_not_retried() {
+        use agent_client_protocol as acp;
+
+        let client = AcpClient::new(MockAgent::new(), AgentConfig::new("mock-cli"));
+        let prompt = Prompt::from("Hello");
+
+        let refused = ResponseCollector::new();
+        refused.set_stop_reason(acp::StopReason::Refusal);
+        let response = client.finish_response(Ok(()), &prompt, &refused).await.unwrap();
+        assert!(response.text.is_empty());
+        assert_eq!(crate::infrastructure::http::finish_reason(response.stop_reason), "content_filter");
+
+        // An empty, normally ended turn still falls back to chat mode
+        let ended = ResponseCollector::new();
+        ended.set_stop_reason(acp::StopReason::EndTurn);
+        let response = client.finish_response(Ok(()), &prompt, &ended).await.unwrap();
+        assert_eq!(response.text.trim(), "Mock response");
+    }
+
+    #[tokio::test]
+    async fn test_futures_are_send() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let client = AcpClient::new(MockAgent::new(), AgentConfig::new("mock-cli"));
+        assert_send(&client.send_prompt("Hello"));
+        assert_send(&client.chat("session", "Hello"));
+        assert_send(&client.prompt_stream("Hello"));
+        assert_send(&client.chat_stream("session", "Hello"));
+    }
 }
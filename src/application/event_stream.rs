@@ -0,0 +1,111 @@
+//! Session event streams
+//!
+//! Adapts a running prompt into a `Stream` of [`SessionEvent`]s.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+use tokio_stream::Stream;
+use tokio_util::sync::DropGuard;
+
+use crate::domain::{AgentResponse, SessionEvent, StopReason};
+use crate::error::{Error, Result};
+
+/// A prompt being answered, boxed so the stream can drive it
+pub(crate) type PromptFuture<'a> = Pin<Box<dyn Future<Output = Result<AgentResponse>> + Send + 'a>>;
+
+/// Live events of a prompt, ending with [`SessionEvent::Stopped`] or [`SessionEvent::Error`]
+///
+/// The stream drives the prompt itself, so it must be polled for the prompt
+/// to make progress. Dropping it cancels the prompt.
+pub struct SessionEventStream<'a> {
+    events: mpsc::UnboundedReceiver<SessionEvent>,
+    prompt: Option<PromptFuture<'a>>,
+    last: Option<SessionEvent>,
+    _cancel_on_drop: DropGuard,
+}
+
+impl<'a> SessionEventStream<'a> {
+    /// Stream the events sent to `events` while `prompt` runs
+    pub(crate) fn new(
+        events: mpsc::UnboundedReceiver<SessionEvent>,
+        prompt: PromptFuture<'a>,
+        cancel_on_drop: DropGuard,
+    ) -> Self {
+        Self {
+            events,
+            prompt: Some(prompt),
+            last: None,
+            _cancel_on_drop: cancel_on_drop,
+        }
+    }
+}
+
+impl Stream for SessionEventStream<'_> {
+    type Item = SessionEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<SessionEvent>> {
+        let this = &mut *self;
+
+        if let Some(prompt) = this.prompt.as_mut() {
+            if let Poll::Ready(result) = prompt.as_mut().poll(cx) {
+                this.prompt = None;
+                this.last = Some(final_event(result));
+            }
+        }
+
+        // Updates are all sent before the prompt finishes, so they come first
+        match this.events.poll_recv(cx) {
+            Poll::Ready(Some(event)) => Poll::Ready(Some(event)),
+            Poll::Ready(None) => Poll::Ready(this.last.take()),
+            Poll::Pending if this.prompt.is_none() => Poll::Ready(this.last.take()),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// The event that ends a prompt's stream
+fn final_event(result: Result<AgentResponse>) -> SessionEvent {
+    match result {
+        Ok(response) => SessionEvent::Stopped(response.stop_reason),
+        Err(Error::Cancelled) => SessionEvent::Stopped(StopReason::Cancelled),
+        Err(e) => SessionEvent::Error(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_stream::StreamExt;
+    use tokio_util::sync::CancellationToken;
+
+    #[tokio::test]
+    async fn test_events_then_stop() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let prompt = Box::pin(async move {
+            tx.send(SessionEvent::MessageChunk("Hello".into())).unwrap();
+            tokio::task::yield_now().await;
+            tx.send(SessionEvent::MessageChunk(" World".into())).unwrap();
+            Ok(AgentResponse::from_text("Hello World"))
+        });
+
+        let stream = SessionEventStream::new(rx, prompt, CancellationToken::new().drop_guard());
+        let events: Vec<_> = stream.collect().await;
+        assert_eq!(events, vec![
+            SessionEvent::MessageChunk("Hello".into()),
+            SessionEvent::MessageChunk(" World".into()),
+            SessionEvent::Stopped(StopReason::EndTurn),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_error_ends_stream() {
+        let (_tx, rx) = mpsc::unbounded_channel();
+        let prompt = Box::pin(async { Err(Error::Timeout) });
+
+        let stream = SessionEventStream::new(rx, prompt, CancellationToken::new().drop_guard());
+        let events: Vec<_> = stream.collect().await;
+        assert!(matches!(events.as_slice(), [SessionEvent::Error(_)]));
+    }
+}
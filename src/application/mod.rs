@@ -3,7 +3,11 @@
 //! Use cases and application services that orchestrate domain logic.
 
 mod client;
+mod event_stream;
+mod registry;
 mod session_service;
 
 pub use client::AcpClient;
+pub use event_stream::SessionEventStream;
+pub use registry::{AgentRegistry, ModelEntry, SharedAgent, DEFAULT_MODEL};
 pub use session_service::SessionService;
@@ -0,0 +1,227 @@
+//! Agent registry
+//!
+//! Routes requests to one of several agents by model name.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::domain::{Agent, AgentConfig, McpServer, Session};
+use crate::error::{Error, Result};
+use super::{AcpClient, SessionService};
+
+/// An agent of any type, so different agents can share a registry
+pub type SharedAgent = Arc<dyn Agent>;
+
+/// Model name that routes to the first registered agent
+pub const DEFAULT_MODEL: &str = "default";
+
+/// A model the registry can route to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelEntry {
+    /// Model name (`agent` or `agent/mode`)
+    pub id: String,
+    /// Name the agent was registered under
+    pub agent: String,
+    /// Agent mode the model selects
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+/// Agents reachable by model name
+///
+/// Each agent is registered under a name, and `name/mode` selects one of its
+/// modes. Every model gets its own client (and process pool); sessions are
+/// shared so they can be looked up by ID alone. Unknown model names are
+/// answered by the default model unless strict matching is enabled.
+pub struct AgentRegistry {
+    models: Vec<ModelEntry>,
+    clients: HashMap<String, Arc<AcpClient<SharedAgent>>>,
+    agents: HashMap<String, (SharedAgent, AgentConfig)>,
+    sessions: SessionService,
+    strict: bool,
+}
+
+impl AgentRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            models: Vec::new(),
+            clients: HashMap::new(),
+            agents: HashMap::new(),
+            sessions: SessionService::new(),
+            strict: false,
+        }
+    }
+
+    /// Reject unknown model names instead of routing them to the default model
+    pub fn with_strict_models(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Register an agent under `name`, replacing any agent with that name
+    ///
+    /// The first agent registered also answers the `default` model.
+    pub fn register(&mut self, name: impl Into<String>, agent: impl Agent + 'static, config: AgentConfig) {
+        let name = name.into();
+        let agent: SharedAgent = Arc::new(agent);
+        info!("[AgentRegistry] Registered {} ({})", name, agent.name());
+
+        self.agents.insert(name.clone(), (agent.clone(), config.clone()));
+        self.add_model(ModelEntry { id: name.clone(), agent: name, mode: None }, agent, config);
+    }
+
+    /// Register an agent (builder style)
+    pub fn with_agent(mut self, name: impl Into<String>, agent: impl Agent + 'static, config: AgentConfig) -> Self {
+        self.register(name, agent, config);
+        self
+    }
+
+    /// Make a mode of a registered agent available as `name/mode`
+    pub fn register_mode(&mut self, name: &str, mode: impl Into<String>) -> Result<()> {
+        let mode = mode.into();
+        let (agent, config) = self.agents.get(name).cloned().ok_or_else(|| Error::agent_not_found(name))?;
+
+        let entry = ModelEntry {
+            id: format!("{}/{}", name, mode),
+            agent: name.to_string(),
+            mode: Some(mode.clone()),
+        };
+        self.add_model(entry, agent, config.with_mode(mode));
+        Ok(())
+    }
+
+    /// Models in registration order
+    pub fn models(&self) -> &[ModelEntry] {
+        &self.models
+    }
+
+    /// The model `model` routes to (`default` is the first registered agent)
+    pub fn model(&self, model: &str) -> Result<&ModelEntry> {
+        if model == DEFAULT_MODEL {
+            return self.models.first().ok_or_else(|| Error::agent_not_found(model));
+        }
+        self.models
+            .iter()
+            .find(|entry| entry.id == model)
+            .ok_or_else(|| Error::agent_not_found(model))
+    }
+
+    /// The model answering requests for `model`
+    ///
+    /// Unknown names go to the default model unless strict matching is on, so
+    /// clients that send a fixed model ID (e.g., `gpt-4o`) keep working.
+    pub fn route(&self, model: &str) -> Result<&ModelEntry> {
+        match self.model(model) {
+            Err(_) if !self.strict => {
+                let entry = self.model(DEFAULT_MODEL)?;
+                debug!("[AgentRegistry] Unknown model {}, using {}", model, entry.id);
+                Ok(entry)
+            }
+            result => result,
+        }
+    }
+
+    /// The client answering `model`
+    pub fn resolve(&self, model: &str) -> Result<Arc<AcpClient<SharedAgent>>> {
+        let entry = self.route(model)?;
+        Ok(self.clients[&entry.id].clone())
+    }
+
+    /// The client answering a session
+    pub fn resolve_session(&self, session: &Session) -> Result<Arc<AcpClient<SharedAgent>>> {
+        self.resolve(session.model.as_deref().unwrap_or(DEFAULT_MODEL))
+    }
+
+    /// Sessions of every agent
+    pub fn sessions(&self) -> &SessionService {
+        &self.sessions
+    }
+
+    /// Create a session answered by `model` (the default model when `None`)
+    pub async fn create_session(
+        &self,
+        model: Option<&str>,
+        system_prompt: Option<String>,
+        mcp_servers: Vec<McpServer>,
+    ) -> Result<Session> {
+        let model = self.route(model.unwrap_or(DEFAULT_MODEL))?.id.clone();
+        let session = self.sessions.create_with_mcp_servers(system_prompt, mcp_servers).await;
+        let session = session.with_model(model);
+        self.sessions.update(session.clone()).await?;
+        Ok(session)
+    }
+
+    /// Add a model (or replace the one with the same ID) with its own client
+    fn add_model(&mut self, entry: ModelEntry, agent: SharedAgent, config: AgentConfig) {
+        let client = AcpClient::new(agent, config).with_sessions(self.sessions.clone());
+        self.clients.insert(entry.id.clone(), Arc::new(client));
+
+        match self.models.iter_mut().find(|existing| existing.id == entry.id) {
+            Some(existing) => *existing = entry,
+            None => self.models.push(entry),
+        }
+    }
+}
+
+impl Default for AgentRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::MockAgent;
+
+    fn registry() -> AgentRegistry {
+        let mut registry = AgentRegistry::new()
+            .with_agent("kiro", MockAgent::new(), AgentConfig::new("kiro-cli"))
+            .with_agent("codex", MockAgent::new(), AgentConfig::new("codex"));
+        registry.register_mode("kiro", "amzn-builder").unwrap();
+        registry
+    }
+
+    #[test]
+    fn test_routes_by_model() {
+        let registry = registry();
+        let ids: Vec<_> = registry.models().iter().map(|entry| entry.id.as_str()).collect();
+        assert_eq!(ids, ["kiro", "codex", "kiro/amzn-builder"]);
+
+        let builder = registry.resolve("kiro/amzn-builder").unwrap();
+        assert_eq!(builder.config().agent_mode.as_deref(), Some("amzn-builder"));
+        assert_eq!(registry.resolve("codex").unwrap().config().cli_path, "codex");
+        assert_eq!(registry.model(DEFAULT_MODEL).unwrap().id, "kiro");
+
+        assert!(matches!(registry.model("gpt-4"), Err(Error::AgentNotFound(_))));
+        assert!(AgentRegistry::new().register_mode("kiro", "dev").is_err());
+        assert!(AgentRegistry::new().resolve("gpt-4").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_models() {
+        let registry = registry();
+        assert_eq!(registry.route("gpt-4o").unwrap().id, "kiro");
+        let session = registry.create_session(Some("gpt-4o"), None, Vec::new()).await.unwrap();
+        assert_eq!(session.model.as_deref(), Some("kiro"));
+
+        let strict = registry.with_strict_models(true);
+        assert!(matches!(strict.resolve("gpt-4o"), Err(Error::AgentNotFound(_))));
+        assert!(strict.create_session(Some("gpt-4o"), None, Vec::new()).await.is_err());
+        assert_eq!(strict.route(DEFAULT_MODEL).unwrap().id, "kiro");
+    }
+
+    #[tokio::test]
+    async fn test_sessions_are_shared() {
+        let registry = registry();
+        let session = registry.create_session(Some("codex"), None, Vec::new()).await.unwrap();
+        assert_eq!(session.model.as_deref(), Some("codex"));
+
+        let client = registry.resolve_session(&session).unwrap();
+        assert_eq!(client.config().cli_path, "codex");
+        assert!(registry.resolve("kiro").unwrap().sessions().exists(&session.id).await);
+    }
+}
@@ -6,7 +6,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use crate::domain::{Message, Session};
+use crate::domain::{McpServer, Message, PlanEntry, Session};
 use crate::error::{Error, Result};
 
 /// Service for managing sessions
@@ -47,6 +47,24 @@ impl SessionService {
         session
     }
 
+    /// Create a session whose ACP session gets extra MCP servers
+    pub async fn create_with_mcp_servers(
+        &self,
+        system_prompt: Option<String>,
+        mcp_servers: Vec<McpServer>,
+    ) -> Session {
+        // Insert it complete, so there is no follow-up update that could fail
+        let session = match system_prompt {
+            Some(prompt) => Session::with_system_prompt(prompt),
+            None => Session::new(),
+        }
+        .with_mcp_servers(mcp_servers);
+
+        let id = session.id.clone();
+        self.sessions.write().await.insert(id, session.clone());
+        session
+    }
+
     /// Get a session by ID
     pub async fn get(&self, id: &str) -> Result<Session> {
         self.sessions
@@ -92,6 +110,16 @@ impl SessionService {
         Ok(())
     }
 
+    /// Replace the agent's plan for a session
+    pub async fn set_plan(&self, session_id: &str, plan: Vec<PlanEntry>) -> Result<()> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| Error::session_not_found(session_id))?;
+        session.set_plan(plan);
+        Ok(())
+    }
+
     /// Get or create a session
     pub async fn get_or_create(&self, id: &str) -> Session {
         if let Ok(session) = self.get(id).await {
@@ -173,4 +201,18 @@ mod tests {
         service.delete(&id).await.unwrap();
         assert!(!service.exists(&id).await);
     }
+
+    #[tokio::test]
+    async fn test_set_plan() {
+        let service = SessionService::new();
+        let session = service.create(None).await;
+        let plan = vec![PlanEntry {
+            content: "Write tests".into(),
+            priority: Default::default(),
+            status: Default::default(),
+        }];
+        service.set_plan(&session.id, plan.clone()).await.unwrap();
+        assert_eq!(service.get(&session.id).await.unwrap().plan, plan);
+        assert!(service.set_plan("nonexistent", plan).await.is_err());
+    }
 }
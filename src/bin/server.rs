@@ -2,8 +2,12 @@
 //!
 //! Starts an HTTP server that exposes agents via OpenAI-compatible endpoints.
 
-use acp_client::{Agent, AgentConfig, KiroAgent, start_server};
+use acp_client::{
+    Agent, AgentConfig, AgentRegistry, CodexAgent, GeminiAgent, GenericAgent, KiroAgent, RulePolicy,
+    start_registry_server,
+};
 use std::env;
+use std::path::Path;
 use std::time::Duration;
 
 #[tokio::main]
@@ -32,6 +36,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let agent_mode = env::var("KIRO_AGENT").ok();
 
+    // Permission rules for every agent (requests are rejected without them)
+    let permissions = match env::var("PERMISSION_RULES") {
+        Ok(path) => Some(load_permission_rules(&path)?),
+        Err(_) => None,
+    };
+    let agent_config = |cli_path: &str| {
+        let config = AgentConfig::new(cli_path).with_timeout(Duration::from_secs(timeout));
+        match permissions {
+            Some(ref policy) => config.with_permission_policy(policy.clone()),
+            None => config,
+        }
+    };
+
     // Create the agent
     let agent = if let Some(ref mode) = agent_mode {
         KiroAgent::with_cli_path(&cli_path).with_mode(mode)
@@ -40,8 +57,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     // Create the config
-    let config = AgentConfig::new(&cli_path)
-        .with_timeout(Duration::from_secs(timeout));
+    let config = agent_config(&cli_path);
 
     let config = if let Some(mode) = agent_mode.clone() {
         config.with_mode(mode)
@@ -49,25 +65,77 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         config
     };
 
+    // Kiro answers the default model; each mode in KIRO_MODES becomes "kiro/<mode>"
+    let agent_name = agent.name().to_string();
+    // Unknown models go to the default agent unless STRICT_MODELS is set
+    let strict = env::var("STRICT_MODELS").is_ok_and(|value| value == "1" || value == "true");
+    let mut registry = AgentRegistry::new()
+        .with_strict_models(strict)
+        .with_agent(&agent_name, agent, config);
+    if let Ok(modes) = env::var("KIRO_MODES") {
+        for mode in modes.split(',').map(str::trim).filter(|mode| !mode.is_empty()) {
+            registry.register_mode(&agent_name, mode)?;
+        }
+    }
+
+    // Other agents are served when their CLI is configured
+    if let Ok(codex_path) = env::var("CODEX_CLI_PATH") {
+        let config = agent_config(&codex_path);
+        registry.register("codex", CodexAgent::with_cli_path(&codex_path), config);
+    }
+    if let Ok(gemini_path) = env::var("GEMINI_CLI_PATH") {
+        let config = agent_config(&gemini_path);
+        let acp = env::var("GEMINI_ACP").is_ok_and(|value| value == "1" || value == "true");
+        registry.register("gemini", GeminiAgent::with_cli_path(&gemini_path).with_acp(acp), config);
+    }
+
+    // Agents described in config files (or directories of them) listed in AGENT_CONFIGS
+    if let Ok(paths) = env::var("AGENT_CONFIGS") {
+        for path in paths.split(',').map(str::trim).filter(|path| !path.is_empty()) {
+            let agents = if Path::new(path).is_dir() {
+                GenericAgent::from_dir(path)?
+            } else {
+                vec![GenericAgent::from_file(path)?]
+            };
+            for agent in agents {
+                let name = agent.name().to_string();
+                let modes = agent.modes().to_vec();
+                let config = agent_config(agent.cli_path());
+                registry.register(&name, agent, config);
+                for mode in modes {
+                    registry.register_mode(&name, mode)?;
+                }
+            }
+        }
+    }
+
     tracing::info!("Starting ACP Server...");
     tracing::info!("Port: {}", port);
-    tracing::info!("Agent: {}", agent.name());
+    tracing::info!("Agent: {}", agent_name);
     tracing::info!("CLI: {}", cli_path);
     tracing::info!("Timeout: {}s", timeout);
     if let Some(ref mode) = agent_mode {
         tracing::info!("Agent mode: {}", mode);
     }
+    if let Ok(path) = env::var("PERMISSION_RULES") {
+        tracing::info!("Permission rules: {}", path);
+    }
+    let models: Vec<&str> = registry.models().iter().map(|model| model.id.as_str()).collect();
+    tracing::info!("Models: {}", models.join(", "));
 
     println!("\n🚀 ACP Server running at http://localhost:{}", port);
     println!("\nOpenAI-compatible endpoints:");
     println!("  POST /v1/chat/completions  - Chat completion");
     println!("  GET  /v1/models            - List models");
+    println!("  GET  /v1/agents/:name      - Agent info and capabilities");
     println!("\nSession management:");
     println!("  POST /v1/sessions              - Create session");
     println!("  GET  /v1/sessions              - List sessions");
     println!("  GET  /v1/sessions/:id          - Get session");
     println!("  DELETE /v1/sessions/:id        - Delete session");
     println!("  POST /v1/sessions/:id/messages - Send message");
+    println!("  POST /v1/sessions/:id/cancel   - Cancel running turn");
+    println!("  GET  /v1/sessions/:id/plan     - Get agent plan");
     println!("\nHealth check:");
     println!("  GET  /health");
     println!("\nExample usage with curl:");
@@ -76,7 +144,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     -d '{{"model": "default", "messages": [{{"role": "user", "content": "Hello!"}}]}}'"#, port);
     println!();
 
-    start_server(agent, config, port).await?;
+    start_registry_server(registry, port).await?;
 
     Ok(())
 }
+
+/// Load a permission rule policy from a TOML or JSON file
+fn load_permission_rules(path: &str) -> Result<RulePolicy, Box<dyn std::error::Error>> {
+    let source = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let policy = if path.ends_with(".json") {
+        serde_json::from_str(&source).map_err(|e| format!("{}: {}", path, e))?
+    } else {
+        toml::from_str(&source).map_err(|e| format!("{}: {}", path, e))?
+    };
+    Ok(policy)
+}
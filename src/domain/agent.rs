@@ -2,21 +2,42 @@
 //!
 //! Defines the abstract interface for AI agents that can be accessed via ACP.
 
+use std::sync::Arc;
 use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use super::output::OutputParser;
 
 /// Information about an agent
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AgentInfo {
     /// Agent name
     pub name: String,
+    /// Human-readable title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
     /// Agent version
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub version: Option<String>,
     /// Agent description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// An authentication method offered by an agent
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthMethod {
+    /// Method ID used with `authenticate`
+    pub id: String,
+    /// Human-readable name
+    pub name: String,
+    /// Description of the method
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 }
 
 /// Capabilities advertised by an agent
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AgentCapabilities {
     /// Whether the agent can load existing sessions
     pub load_session: bool,
@@ -24,10 +45,44 @@ pub struct AgentCapabilities {
     pub image: bool,
     /// Whether the agent supports audio in prompts
     pub audio: bool,
+    /// Whether the agent accepts embedded resources in prompts
+    pub embedded_context: bool,
+    /// Whether the agent can connect to MCP servers over HTTP
+    pub mcp_http: bool,
+    /// Whether the agent can connect to MCP servers over SSE
+    pub mcp_sse: bool,
+    /// Authentication methods the agent offers
+    pub auth_methods: Vec<AuthMethod>,
     /// Available models
     pub available_models: Vec<String>,
+    /// Model new sessions start with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_model: Option<String>,
     /// Available modes/agents
     pub available_modes: Vec<String>,
+    /// Mode new sessions start in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_mode: Option<String>,
+}
+
+/// How the client talks to an agent's CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentTransport {
+    /// ACP (JSON-RPC) over stdio, with a long-lived process per connection
+    #[default]
+    Acp,
+    /// One process per prompt: the prompt on stdin, the answer on stdout
+    Cli,
+    /// Like `Cli`, but stdout is streamed line by line as the answer grows
+    StreamingCli,
+}
+
+impl AgentTransport {
+    /// Whether the agent runs one process per prompt instead of speaking ACP
+    pub fn is_cli(self) -> bool {
+        self != Self::Acp
+    }
 }
 
 /// Trait defining the interface for an AI agent
@@ -49,29 +104,119 @@ pub trait Agent: Send + Sync {
     /// Get the CLI arguments for non-interactive chat mode (fallback)
     fn chat_args(&self) -> Vec<String>;
 
+    /// CLI arguments that start the agent in `mode` (e.g., `--agent <mode>`)
+    fn mode_args(&self, mode: &str) -> Vec<String> {
+        vec!["--agent".to_string(), mode.to_string()]
+    }
+
+    /// How prompts reach the agent
+    ///
+    /// CLI transports run `chat_args` once per prompt; `acp_args` is only
+    /// used by ACP agents.
+    fn transport(&self) -> AgentTransport {
+        AgentTransport::Acp
+    }
+
     /// Whether this agent requires mcpServers in session/new request
     fn requires_mcp_servers(&self) -> bool {
         true
     }
 
-    /// Delay after session creation before sending prompts
-    /// (some agents need time to initialize MCP servers)
+    /// Authentication methods to use, in order of preference
+    ///
+    /// After `initialize`, the client authenticates with the first one the
+    /// agent offers. Agents that handle their own login return none.
+    fn auth_methods(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Longest wait after the first session is created for the agent to
+    /// report it ready (some agents need time to initialize MCP servers)
+    ///
+    /// The wait ends early when the agent sends its available commands.
     fn session_init_delay(&self) -> Duration {
-        Duration::from_secs(2)
+        Duration::ZERO
     }
 
-    /// Delay after prompt completion before closing
+    /// Extra delay after a prompt's updates have been handled
+    ///
+    /// Only needed for agents that send updates after answering the prompt.
     fn post_prompt_delay(&self) -> Duration {
-        Duration::from_millis(500)
+        Duration::ZERO
     }
 
     /// Process/clean response text (e.g., strip ANSI codes)
+    ///
+    /// Runs on complete answers only; streamed chunks are passed on raw.
     fn process_response(&self, response: &str) -> String {
         response.to_string()
     }
 
+    /// A parser for the structured output of CLI transports
+    ///
+    /// Without one, each line of output is answer text.
+    fn output_parser(&self) -> Option<Box<dyn OutputParser>> {
+        None
+    }
+
     /// Get agent-specific environment variables
     fn environment(&self) -> Vec<(String, String)> {
         vec![]
     }
 }
+
+/// Shared agents (e.g., `Arc<dyn Agent>`) are agents too
+#[async_trait::async_trait]
+impl<A: Agent + ?Sized> Agent for Arc<A> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn cli_path(&self) -> &str {
+        (**self).cli_path()
+    }
+
+    fn acp_args(&self) -> Vec<String> {
+        (**self).acp_args()
+    }
+
+    fn chat_args(&self) -> Vec<String> {
+        (**self).chat_args()
+    }
+
+    fn mode_args(&self, mode: &str) -> Vec<String> {
+        (**self).mode_args(mode)
+    }
+
+    fn transport(&self) -> AgentTransport {
+        (**self).transport()
+    }
+
+    fn requires_mcp_servers(&self) -> bool {
+        (**self).requires_mcp_servers()
+    }
+
+    fn auth_methods(&self) -> Vec<String> {
+        (**self).auth_methods()
+    }
+
+    fn session_init_delay(&self) -> Duration {
+        (**self).session_init_delay()
+    }
+
+    fn post_prompt_delay(&self) -> Duration {
+        (**self).post_prompt_delay()
+    }
+
+    fn process_response(&self, response: &str) -> String {
+        (**self).process_response(response)
+    }
+
+    fn output_parser(&self) -> Option<Box<dyn OutputParser>> {
+        (**self).output_parser()
+    }
+
+    fn environment(&self) -> Vec<(String, String)> {
+        (**self).environment()
+    }
+}
@@ -2,8 +2,12 @@
 //!
 //! Configuration value objects for agents.
 
+use std::sync::Arc;
 use std::time::Duration;
 
+use super::mcp::McpServer;
+use super::permission::{DenyAll, PermissionPolicy};
+
 /// Configuration for an agent
 #[derive(Debug, Clone)]
 pub struct AgentConfig {
@@ -19,6 +23,22 @@ pub struct AgentConfig {
     pub extra_args: Vec<String>,
     /// Working directory for the agent
     pub working_dir: Option<String>,
+    /// Maximum number of warm agent processes kept in the pool
+    pub pool_size: usize,
+    /// How long an unused agent process stays alive before being reaped
+    pub idle_timeout: Duration,
+    /// Policy answering the agent's permission requests
+    pub permission_policy: Arc<dyn PermissionPolicy>,
+    /// Commands the agent may run in client-side terminals (`*` allows any)
+    pub terminal_commands: Vec<String>,
+    /// MCP servers given to every session
+    pub mcp_servers: Vec<McpServer>,
+    /// Largest file (in bytes) embedded in a prompt; larger files are linked
+    pub max_resource_size: u64,
+    /// Overrides the agent's longest wait for session readiness
+    pub session_init_delay: Option<Duration>,
+    /// Overrides the agent's extra delay after each prompt
+    pub post_prompt_delay: Option<Duration>,
 }
 
 impl AgentConfig {
@@ -31,6 +51,14 @@ impl AgentConfig {
             timeout: Duration::from_secs(120),
             extra_args: vec![],
             working_dir: None,
+            pool_size: 4,
+            idle_timeout: Duration::from_secs(300),
+            permission_policy: Arc::new(DenyAll),
+            terminal_commands: vec![],
+            mcp_servers: vec![],
+            max_resource_size: 1024 * 1024,
+            session_init_delay: None,
+            post_prompt_delay: None,
         }
     }
 
@@ -63,6 +91,56 @@ impl AgentConfig {
         self.working_dir = Some(dir.into());
         self
     }
+
+    /// Set the maximum number of pooled agent processes (at least 1)
+    pub fn with_pool_size(mut self, size: usize) -> Self {
+        self.pool_size = size.max(1);
+        self
+    }
+
+    /// Set how long idle agent processes are kept alive
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// Set the policy answering permission requests (default: reject all)
+    pub fn with_permission_policy(mut self, policy: impl PermissionPolicy + 'static) -> Self {
+        self.permission_policy = Arc::new(policy);
+        self
+    }
+
+    /// Allow the agent to run these commands in client-side terminals
+    pub fn with_terminal_commands(mut self, commands: Vec<String>) -> Self {
+        self.terminal_commands = commands;
+        self
+    }
+
+    /// Give every session these MCP servers
+    pub fn with_mcp_servers(mut self, servers: Vec<McpServer>) -> Self {
+        self.mcp_servers = servers;
+        self
+    }
+
+    /// Set the largest file embedded in a prompt (default: 1 MiB)
+    pub fn with_max_resource_size(mut self, bytes: u64) -> Self {
+        self.max_resource_size = bytes;
+        self
+    }
+
+    /// Wait at most this long for the agent to report its first session ready
+    pub fn with_session_init_delay(mut self, delay: Duration) -> Self {
+        self.session_init_delay = Some(delay);
+        self
+    }
+
+    /// Sleep this long after each prompt's updates have been handled
+    ///
+    /// Only needed for agents that send updates after answering the prompt.
+    pub fn with_post_prompt_delay(mut self, delay: Duration) -> Self {
+        self.post_prompt_delay = Some(delay);
+        self
+    }
 }
 
 impl Default for AgentConfig {
@@ -0,0 +1,219 @@
+//! Prompt content
+//!
+//! Text, images, audio and documents sent to an agent as one prompt.
+
+use serde::{Deserialize, Serialize};
+
+use super::agent::AgentCapabilities;
+use crate::error::{Error, Result};
+
+/// A piece of message content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    /// Plain text
+    Text { text: String },
+    /// Base64-encoded image
+    Image { data: String, mime_type: String },
+    /// Base64-encoded audio
+    Audio { data: String, mime_type: String },
+    /// A file in the working directory (or a `file:` URI), read when the prompt is sent
+    File { path: String },
+    /// A document embedded in the prompt
+    Resource {
+        uri: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mime_type: Option<String>,
+        text: String,
+    },
+    /// A resource the agent can read itself
+    ResourceLink {
+        uri: String,
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mime_type: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        size: Option<u64>,
+    },
+}
+
+impl ContentPart {
+    /// Create a text part
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text { text: text.into() }
+    }
+
+    /// Create an image part from base64 data
+    pub fn image(data: impl Into<String>, mime_type: impl Into<String>) -> Self {
+        Self::Image { data: data.into(), mime_type: mime_type.into() }
+    }
+
+    /// Create an audio part from base64 data
+    pub fn audio(data: impl Into<String>, mime_type: impl Into<String>) -> Self {
+        Self::Audio { data: data.into(), mime_type: mime_type.into() }
+    }
+
+    /// Create a file part from a path relative to the working directory
+    pub fn file(path: impl Into<String>) -> Self {
+        Self::File { path: path.into() }
+    }
+
+    /// Create an embedded document part
+    pub fn resource(uri: impl Into<String>, text: impl Into<String>) -> Self {
+        Self::Resource { uri: uri.into(), mime_type: None, text: text.into() }
+    }
+
+    /// Create a resource link part
+    pub fn resource_link(uri: impl Into<String>, name: impl Into<String>) -> Self {
+        Self::ResourceLink { uri: uri.into(), name: name.into(), mime_type: None, size: None }
+    }
+
+    /// Kind of content ("text", "image", "audio", "file", "resource" or "resource_link")
+    pub fn modality(&self) -> &'static str {
+        match self {
+            Self::Text { .. } => "text",
+            Self::Image { .. } => "image",
+            Self::Audio { .. } => "audio",
+            Self::File { .. } => "file",
+            Self::Resource { .. } => "resource",
+            Self::ResourceLink { .. } => "resource_link",
+        }
+    }
+
+    /// Whether the agent accepts this kind of content in prompts
+    ///
+    /// Files are always accepted: they are linked instead of embedded when the
+    /// agent doesn't take embedded resources.
+    pub fn is_supported_by(&self, capabilities: &AgentCapabilities) -> bool {
+        match self {
+            Self::Text { .. } | Self::File { .. } | Self::ResourceLink { .. } => true,
+            Self::Image { .. } => capabilities.image,
+            Self::Audio { .. } => capabilities.audio,
+            Self::Resource { .. } => capabilities.embedded_context,
+        }
+    }
+}
+
+/// The content of a prompt
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Prompt {
+    parts: Vec<ContentPart>,
+}
+
+impl Prompt {
+    /// Create a prompt from content parts
+    pub fn new(parts: Vec<ContentPart>) -> Self {
+        Self { parts }
+    }
+
+    /// Add a content part
+    pub fn with_part(mut self, part: ContentPart) -> Self {
+        self.parts.push(part);
+        self
+    }
+
+    /// Add several content parts
+    pub fn with_parts(mut self, parts: impl IntoIterator<Item = ContentPart>) -> Self {
+        self.parts.extend(parts);
+        self
+    }
+
+    /// All content parts
+    pub fn parts(&self) -> &[ContentPart] {
+        &self.parts
+    }
+
+    /// The text parts, joined by blank lines
+    pub fn text(&self) -> String {
+        self.parts
+            .iter()
+            .filter_map(|part| match part {
+                ContentPart::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Whether the prompt references files that still need to be read
+    pub fn has_files(&self) -> bool {
+        self.parts.iter().any(|part| matches!(part, ContentPart::File { .. }))
+    }
+
+    /// The non-text parts
+    pub fn attachments(&self) -> impl Iterator<Item = &ContentPart> {
+        self.parts.iter().filter(|part| !matches!(part, ContentPart::Text { .. }))
+    }
+
+    /// Whether the prompt carries anything besides text
+    pub fn has_attachments(&self) -> bool {
+        self.attachments().next().is_some()
+    }
+
+    /// Check that the agent accepts every kind of content in the prompt
+    pub fn check_supported(&self, capabilities: &AgentCapabilities) -> Result<()> {
+        match self.parts.iter().find(|part| !part.is_supported_by(capabilities)) {
+            Some(part) => Err(Error::unsupported_content(part.modality())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl From<&str> for Prompt {
+    fn from(text: &str) -> Self {
+        Self::new(vec![ContentPart::text(text)])
+    }
+}
+
+impl From<&String> for Prompt {
+    fn from(text: &String) -> Self {
+        Self::from(text.as_str())
+    }
+}
+
+impl From<String> for Prompt {
+    fn from(text: String) -> Self {
+        Self::new(vec![ContentPart::text(text)])
+    }
+}
+
+impl From<Vec<ContentPart>> for Prompt {
+    fn from(parts: Vec<ContentPart>) -> Self {
+        Self::new(parts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_and_attachments() {
+        let prompt = Prompt::from("Describe this")
+            .with_part(ContentPart::image("aGVsbG8=", "image/png"))
+            .with_part(ContentPart::text("Be brief"));
+
+        assert_eq!(prompt.text(), "Describe this\n\nBe brief");
+        assert_eq!(prompt.attachments().count(), 1);
+        assert!(prompt.has_attachments());
+    }
+
+    #[test]
+    fn test_check_supported() {
+        let capabilities = AgentCapabilities { image: true, ..Default::default() };
+        let image = Prompt::from("Hi").with_part(ContentPart::image("aGVsbG8=", "image/png"));
+        let audio = Prompt::from("Hi").with_part(ContentPart::audio("aGVsbG8=", "audio/wav"));
+
+        assert!(image.check_supported(&capabilities).is_ok());
+        assert!(matches!(
+            audio.check_supported(&capabilities),
+            Err(Error::UnsupportedContent(modality)) if modality == "audio"
+        ));
+
+        let document = Prompt::from("Hi").with_part(ContentPart::resource("untitled:notes.md", "# Notes"));
+        assert!(document.check_supported(&capabilities).is_err());
+        let file = Prompt::from("Hi").with_part(ContentPart::file("src/lib.rs"));
+        assert!(file.check_supported(&capabilities).is_ok());
+    }
+}
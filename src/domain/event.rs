@@ -0,0 +1,49 @@
+//! Session events
+//!
+//! Typed progress updates emitted while an agent works on a prompt.
+
+use serde::{Deserialize, Serialize};
+
+use super::response::{PlanEntry, StopReason, TokenUsage, ToolCall};
+
+/// A command the agent offers (e.g., slash commands)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AvailableCommand {
+    /// Command name
+    pub name: String,
+    /// What the command does
+    pub description: String,
+}
+
+/// Something that happened while the agent was answering a prompt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum SessionEvent {
+    /// A piece of the answer text
+    MessageChunk(String),
+    /// A piece of the agent's reasoning
+    ThoughtChunk(String),
+    /// The agent started a tool call
+    ToolCallStarted(ToolCall),
+    /// A tool call changed (carries its latest state)
+    ToolCallUpdated(ToolCall),
+    /// The agent's plan changed (carries the whole plan)
+    Plan(Vec<PlanEntry>),
+    /// The session switched to another mode
+    ModeChanged(String),
+    /// The commands the agent offers changed
+    AvailableCommands(Vec<AvailableCommand>),
+    /// The agent reported how many tokens the prompt used
+    Usage(TokenUsage),
+    /// The agent finished its turn; no more events follow
+    Stopped(StopReason),
+    /// The prompt failed; no more events follow
+    Error(String),
+}
+
+impl SessionEvent {
+    /// Whether this is the last event of a prompt
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Stopped(_) | Self::Error(_))
+    }
+}
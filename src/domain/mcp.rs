@@ -0,0 +1,132 @@
+//! MCP server definitions
+//!
+//! MCP servers handed to the agent when a session is created, so it can use
+//! our tools without touching the CLI's global configuration.
+
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+/// An MCP server the agent should connect to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum McpServer {
+    /// Server launched by the agent as a subprocess
+    Stdio {
+        /// Server name
+        name: String,
+        /// Command to run
+        command: String,
+        /// Command arguments
+        #[serde(default)]
+        args: Vec<String>,
+        /// Environment variables for the server process
+        #[serde(default)]
+        env: BTreeMap<String, String>,
+    },
+    /// Server reached over streamable HTTP (requires agent support)
+    Http {
+        /// Server name
+        name: String,
+        /// Server URL
+        url: String,
+        /// HTTP headers sent with each request
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+    /// Server reached over SSE (requires agent support)
+    Sse {
+        /// Server name
+        name: String,
+        /// Server URL
+        url: String,
+        /// HTTP headers sent with each request
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+}
+
+impl McpServer {
+    /// Create a stdio server definition
+    pub fn stdio(name: impl Into<String>, command: impl Into<String>, args: Vec<String>) -> Self {
+        Self::Stdio {
+            name: name.into(),
+            command: command.into(),
+            args,
+            env: BTreeMap::new(),
+        }
+    }
+
+    /// Create an HTTP server definition
+    pub fn http(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self::Http {
+            name: name.into(),
+            url: url.into(),
+            headers: BTreeMap::new(),
+        }
+    }
+
+    /// Create an SSE server definition
+    pub fn sse(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self::Sse {
+            name: name.into(),
+            url: url.into(),
+            headers: BTreeMap::new(),
+        }
+    }
+
+    /// Add an environment variable (stdio servers only)
+    pub fn with_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        if let Self::Stdio { ref mut env, .. } = self {
+            env.insert(key.into(), value.into());
+        }
+        self
+    }
+
+    /// Add an HTTP header (HTTP and SSE servers only)
+    pub fn with_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        if let Self::Http { ref mut headers, .. } | Self::Sse { ref mut headers, .. } = self {
+            headers.insert(key.into(), value.into());
+        }
+        self
+    }
+
+    /// Server name
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Stdio { name, .. } | Self::Http { name, .. } | Self::Sse { name, .. } => name,
+        }
+    }
+
+    /// Transport name ("stdio", "http" or "sse")
+    pub fn transport(&self) -> &'static str {
+        match self {
+            Self::Stdio { .. } => "stdio",
+            Self::Http { .. } => "http",
+            Self::Sse { .. } => "sse",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_specs() {
+        let servers: Vec<McpServer> = serde_json::from_value(serde_json::json!([
+            {"type": "stdio", "name": "tools", "command": "tools-mcp", "env": {"TOKEN": "x"}},
+            {"type": "http", "name": "search", "url": "http://localhost:9000/mcp"},
+        ]))
+        .unwrap();
+
+        assert_eq!(servers[0], McpServer::stdio("tools", "tools-mcp", vec![]).with_env("TOKEN", "x"));
+        assert_eq!(servers[1].transport(), "http");
+        assert_eq!(servers[1].name(), "search");
+    }
+
+    #[test]
+    fn test_builders_ignore_other_transports() {
+        let server = McpServer::stdio("tools", "tools-mcp", vec![]).with_header("Authorization", "x");
+        assert_eq!(server, McpServer::stdio("tools", "tools-mcp", vec![]));
+    }
+}
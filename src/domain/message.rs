@@ -5,6 +5,8 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 
+use super::content::{ContentPart, Prompt};
+
 /// Role of the message sender
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -48,11 +50,17 @@ impl std::str::FromStr for Role {
 pub struct Message {
     /// Role of the sender
     pub role: Role,
-    /// Message content
+    /// Message content (the text parts, for multimodal messages)
     pub content: String,
+    /// Content parts, when the message carries more than text
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<ContentPart>,
     /// Optional name/identifier for the sender
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    /// Reasoning the agent shared while writing this message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
     /// Timestamp when the message was created
     pub timestamp: DateTime<Utc>,
 }
@@ -63,11 +71,38 @@ impl Message {
         Self {
             role,
             content: content.into(),
+            parts: Vec::new(),
             name: None,
+            reasoning: None,
             timestamp: Utc::now(),
         }
     }
 
+    /// Create a message from prompt content
+    ///
+    /// Text-only content is stored as plain `content`.
+    pub fn from_prompt(role: Role, prompt: &Prompt) -> Self {
+        let mut message = Self::new(role, prompt.text());
+        if prompt.has_attachments() {
+            message.parts = prompt.parts().to_vec();
+        }
+        message
+    }
+
+    /// The message content as a prompt
+    pub fn prompt(&self) -> Prompt {
+        if self.parts.is_empty() {
+            Prompt::from(self.content.as_str())
+        } else {
+            Prompt::new(self.parts.clone())
+        }
+    }
+
+    /// The non-text content parts
+    pub fn attachments(&self) -> impl Iterator<Item = &ContentPart> {
+        self.parts.iter().filter(|part| !matches!(part, ContentPart::Text { .. }))
+    }
+
     /// Create a system message
     pub fn system(content: impl Into<String>) -> Self {
         Self::new(Role::System, content)
@@ -88,4 +123,10 @@ impl Message {
         self.name = Some(name.into());
         self
     }
+
+    /// Attach the agent's reasoning
+    pub fn with_reasoning(mut self, reasoning: impl Into<String>) -> Self {
+        self.reasoning = Some(reasoning.into());
+        self
+    }
 }
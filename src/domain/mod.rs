@@ -5,10 +5,29 @@
 
 mod agent;
 mod config;
+mod content;
+mod event;
+mod mcp;
 pub mod message;
+mod output;
+mod permission;
+mod response;
 mod session;
 
-pub use agent::{Agent, AgentCapabilities, AgentInfo};
+pub use agent::{Agent, AgentCapabilities, AgentInfo, AgentTransport, AuthMethod};
 pub use config::AgentConfig;
+pub use content::{ContentPart, Prompt};
+pub use event::{AvailableCommand, SessionEvent};
+pub use mcp::McpServer;
 pub use message::{Message, Role};
+pub use output::OutputParser;
+pub use permission::{
+    AllowAll, DenyAll, PermissionAction, PermissionDecision, PermissionOption,
+    PermissionOptionKind, PermissionPolicy, PermissionRequest, PermissionRule, PermissionRuleConfig,
+    RulePolicy, RulePolicyConfig,
+};
+pub use response::{
+    AgentResponse, PlanEntry, PlanEntryPriority, PlanEntryStatus, ResponseTimings, StopReason,
+    TokenUsage, ToolCall, ToolCallLocation, ToolCallStatus,
+};
 pub use session::Session;
@@ -0,0 +1,19 @@
+//! CLI output parsing
+//!
+//! Turns the event streams some CLIs print (one JSON object per line) into
+//! session events.
+
+use super::event::SessionEvent;
+
+/// Parses an agent's line-by-line output into session events
+///
+/// A parser lives for one prompt, so it can keep state between lines (e.g.,
+/// tool calls still running). Answer text is reported as
+/// [`SessionEvent::MessageChunk`], failures as [`SessionEvent::Error`] and the
+/// end of the turn as [`SessionEvent::Stopped`].
+pub trait OutputParser: Send {
+    /// Parse one line of output (without its newline)
+    ///
+    /// Lines that carry nothing of interest yield no events.
+    fn parse_line(&mut self, line: &str) -> Vec<SessionEvent>;
+}
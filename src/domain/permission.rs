@@ -0,0 +1,533 @@
+//! Permission policies
+//!
+//! Decide how to answer an agent's request for permission to run a tool.
+
+use std::fmt::Debug;
+use std::path::{Component, Path, PathBuf};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// Kind of an option offered with a permission request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionOptionKind {
+    AllowOnce,
+    AllowAlways,
+    RejectOnce,
+    RejectAlways,
+}
+
+/// An option the agent offers for a permission request
+#[derive(Debug, Clone)]
+pub struct PermissionOption {
+    /// Option ID sent back to the agent
+    pub id: String,
+    /// Human-readable label
+    pub name: String,
+    /// What choosing this option means
+    pub kind: PermissionOptionKind,
+}
+
+/// A tool call the agent wants permission to run
+#[derive(Debug, Clone, Default)]
+pub struct PermissionRequest {
+    /// ACP session the tool call belongs to
+    pub session_id: String,
+    /// Tool call ID
+    pub tool_call_id: String,
+    /// Tool kind as named by ACP (e.g., "edit", "execute")
+    pub kind: Option<String>,
+    /// Tool call title
+    pub title: Option<String>,
+    /// File paths the tool call touches
+    pub paths: Vec<String>,
+    /// Session working directory, which relative paths are resolved against
+    pub cwd: Option<String>,
+    /// Command line, for tool calls that run commands
+    pub command: Option<String>,
+    /// Options offered by the agent
+    pub options: Vec<PermissionOption>,
+}
+
+impl PermissionRequest {
+    /// Find the offered option that carries out `action`
+    ///
+    /// Never picks a broader grant than asked for: `AllowOnce` only matches an
+    /// allow-once option, while `AllowAlways` falls back to allow-once.
+    pub fn option_for(&self, action: PermissionAction) -> Option<&PermissionOption> {
+        let preferred: &[PermissionOptionKind] = match action {
+            PermissionAction::AllowOnce => &[PermissionOptionKind::AllowOnce],
+            PermissionAction::AllowAlways => {
+                &[PermissionOptionKind::AllowAlways, PermissionOptionKind::AllowOnce]
+            }
+            PermissionAction::Reject => {
+                &[PermissionOptionKind::RejectOnce, PermissionOptionKind::RejectAlways]
+            }
+        };
+
+        preferred
+            .iter()
+            .find_map(|kind| self.options.iter().find(|option| option.kind == *kind))
+    }
+}
+
+/// What a policy wants done with a permission request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionAction {
+    /// Allow this tool call only
+    AllowOnce,
+    /// Allow this tool call and let the agent remember it
+    AllowAlways,
+    /// Refuse the tool call
+    #[default]
+    Reject,
+}
+
+/// A policy's answer to a permission request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionDecision {
+    /// Selected option ID, or `None` to cancel the request
+    pub option_id: Option<String>,
+    /// Why the decision was made (for logging)
+    pub reason: String,
+}
+
+impl PermissionDecision {
+    /// Carry out `action` with the matching offered option
+    ///
+    /// Cancels the request when the agent offered no such option.
+    pub fn for_action(
+        request: &PermissionRequest,
+        action: PermissionAction,
+        reason: impl Into<String>,
+    ) -> Self {
+        let reason = reason.into();
+        match request.option_for(action) {
+            Some(option) => Self {
+                option_id: Some(option.id.clone()),
+                reason,
+            },
+            None => Self {
+                option_id: None,
+                reason: format!("{} (no {:?} option offered)", reason, action),
+            },
+        }
+    }
+
+    /// Whether the request is cancelled rather than answered
+    pub fn is_cancelled(&self) -> bool {
+        self.option_id.is_none()
+    }
+}
+
+/// Decides how to answer permission requests from an agent
+pub trait PermissionPolicy: Debug + Send + Sync {
+    /// Pick one of the offered options (or cancel) for a request
+    fn decide(&self, request: &PermissionRequest) -> PermissionDecision;
+}
+
+/// Rejects every request (the default)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DenyAll;
+
+impl PermissionPolicy for DenyAll {
+    fn decide(&self, request: &PermissionRequest) -> PermissionDecision {
+        PermissionDecision::for_action(request, PermissionAction::Reject, "deny-all policy")
+    }
+}
+
+/// Allows every request once
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllowAll;
+
+impl PermissionPolicy for AllowAll {
+    fn decide(&self, request: &PermissionRequest) -> PermissionDecision {
+        PermissionDecision::for_action(request, PermissionAction::AllowOnce, "allow-all policy")
+    }
+}
+
+/// A rule matching permission requests
+///
+/// All conditions that are set must match; a rule without conditions matches
+/// every request.
+#[derive(Debug, Clone)]
+pub struct PermissionRule {
+    action: PermissionAction,
+    kinds: Vec<String>,
+    title: Option<Regex>,
+    paths: Vec<Regex>,
+    command: Option<Regex>,
+}
+
+impl PermissionRule {
+    /// Create a rule that applies `action`
+    pub fn new(action: PermissionAction) -> Self {
+        Self {
+            action,
+            kinds: Vec::new(),
+            title: None,
+            paths: Vec::new(),
+            command: None,
+        }
+    }
+
+    /// Create a rule that allows matching tool calls once
+    pub fn allow_once() -> Self {
+        Self::new(PermissionAction::AllowOnce)
+    }
+
+    /// Create a rule that allows matching tool calls permanently
+    pub fn allow_always() -> Self {
+        Self::new(PermissionAction::AllowAlways)
+    }
+
+    /// Create a rule that rejects matching tool calls
+    pub fn reject() -> Self {
+        Self::new(PermissionAction::Reject)
+    }
+
+    /// Match a tool kind (e.g., "edit"); may be given several times
+    pub fn with_kind(mut self, kind: impl Into<String>) -> Self {
+        self.kinds.push(kind.into());
+        self
+    }
+
+    /// Match titles against a regular expression
+    pub fn with_title(mut self, pattern: &str) -> Result<Self> {
+        self.title = Some(compile(pattern)?);
+        Ok(self)
+    }
+
+    /// Require every touched path to match one of the given globs
+    ///
+    /// `*` and `?` stay within a path segment, `**` crosses segments.
+    pub fn with_path(mut self, glob: &str) -> Self {
+        self.paths.push(glob_to_regex(glob));
+        self
+    }
+
+    /// Match the command line against a regular expression
+    pub fn with_command(mut self, pattern: &str) -> Result<Self> {
+        self.command = Some(compile(pattern)?);
+        Ok(self)
+    }
+
+    /// Action taken when the rule matches
+    pub fn action(&self) -> PermissionAction {
+        self.action
+    }
+
+    /// Whether the rule applies to a request
+    pub fn matches(&self, request: &PermissionRequest) -> bool {
+        if !self.kinds.is_empty() {
+            match request.kind {
+                Some(ref kind) if self.kinds.contains(kind) => {}
+                _ => return false,
+            }
+        }
+
+        if let Some(ref title) = self.title {
+            if !request.title.as_deref().is_some_and(|t| title.is_match(t)) {
+                return false;
+            }
+        }
+
+        if !self.paths.is_empty() {
+            if request.paths.is_empty() {
+                return false;
+            }
+            let all_match = request.paths.iter().all(|path| {
+                normalize_path(path, request.cwd.as_deref())
+                    .is_some_and(|path| self.paths.iter().any(|glob| glob.is_match(&path)))
+            });
+            if !all_match {
+                return false;
+            }
+        }
+
+        if let Some(ref command) = self.command {
+            if !request.command.as_deref().is_some_and(|c| command.is_match(c)) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// A permission rule as written in a config file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PermissionRuleConfig {
+    /// Action taken when the rule matches
+    pub action: PermissionAction,
+    /// Tool kinds to match
+    #[serde(default)]
+    pub kinds: Vec<String>,
+    /// Regular expression for titles
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Globs every touched path must match one of
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Regular expression for command lines
+    #[serde(default)]
+    pub command: Option<String>,
+}
+
+impl TryFrom<PermissionRuleConfig> for PermissionRule {
+    type Error = Error;
+
+    fn try_from(config: PermissionRuleConfig) -> Result<Self> {
+        let mut rule = Self::new(config.action);
+        rule.kinds = config.kinds;
+        rule.title = config.title.as_deref().map(compile).transpose()?;
+        rule.paths = config.paths.iter().map(|glob| glob_to_regex(glob)).collect();
+        rule.command = config.command.as_deref().map(compile).transpose()?;
+        Ok(rule)
+    }
+}
+
+/// A rule policy as written in a config file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RulePolicyConfig {
+    /// Action for requests no rule matches (default: reject)
+    #[serde(default)]
+    pub default: PermissionAction,
+    /// Rules, checked in order
+    #[serde(default)]
+    pub rules: Vec<PermissionRuleConfig>,
+}
+
+impl TryFrom<RulePolicyConfig> for RulePolicy {
+    type Error = Error;
+
+    fn try_from(config: RulePolicyConfig) -> Result<Self> {
+        let rules = config.rules.into_iter().map(PermissionRule::try_from).collect::<Result<_>>()?;
+        Ok(Self { rules, default: config.default })
+    }
+}
+
+/// Policy that applies the first matching rule
+///
+/// Deserializes from a `RulePolicyConfig`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RulePolicyConfig")]
+pub struct RulePolicy {
+    rules: Vec<PermissionRule>,
+    default: PermissionAction,
+}
+
+impl RulePolicy {
+    /// Create an empty policy that rejects anything no rule matches
+    pub fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            default: PermissionAction::Reject,
+        }
+    }
+
+    /// Add a rule; rules are checked in the order they were added
+    pub fn with_rule(mut self, rule: PermissionRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Set the action for requests no rule matches
+    pub fn with_default(mut self, action: PermissionAction) -> Self {
+        self.default = action;
+        self
+    }
+}
+
+impl Default for RulePolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PermissionPolicy for RulePolicy {
+    fn decide(&self, request: &PermissionRequest) -> PermissionDecision {
+        match self.rules.iter().position(|rule| rule.matches(request)) {
+            Some(index) => PermissionDecision::for_action(
+                request,
+                self.rules[index].action,
+                format!("matched rule #{}", index + 1),
+            ),
+            None => PermissionDecision::for_action(request, self.default, "no rule matched"),
+        }
+    }
+}
+
+/// Compile a user-supplied regular expression
+fn compile(pattern: &str) -> Result<Regex> {
+    Regex::new(pattern).map_err(|e| Error::config(format!("Invalid pattern '{}': {}", pattern, e)))
+}
+
+/// Translate a path glob into an anchored regular expression
+fn glob_to_regex(glob: &str) -> Regex {
+    let mut pattern = String::from("^");
+    let mut chars = glob.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    // `**/` also matches zero directories
+                    chars.next();
+                    pattern.push_str("(?:.*/)?");
+                } else {
+                    pattern.push_str(".*");
+                }
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    pattern.push('$');
+    Regex::new(&pattern).expect("escaped glob is a valid regex")
+}
+
+/// Resolve a path against `cwd`, then `.` and `..` lexically
+///
+/// Returns `None` for paths that climb above their root, so they match no glob.
+fn normalize_path(path: &str, cwd: Option<&str>) -> Option<String> {
+    let path = match cwd {
+        Some(cwd) if Path::new(path).is_relative() => Path::new(cwd).join(path),
+        _ => PathBuf::from(path),
+    };
+
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    Some(normalized.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn option(id: &str, kind: PermissionOptionKind) -> PermissionOption {
+        PermissionOption {
+            id: id.into(),
+            name: id.into(),
+            kind,
+        }
+    }
+
+    fn edit_request(path: &str) -> PermissionRequest {
+        PermissionRequest {
+            kind: Some("edit".into()),
+            title: Some(format!("Edit {}", path)),
+            paths: vec![path.into()],
+            options: vec![
+                option("allow", PermissionOptionKind::AllowOnce),
+                option("reject", PermissionOptionKind::RejectOnce),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_glob_to_regex() {
+        let glob = glob_to_regex("/work/**/*.rs");
+        assert!(glob.is_match("/work/main.rs"));
+        assert!(glob.is_match("/work/src/lib/mod.rs"));
+        assert!(!glob.is_match("/work/src/notes.md"));
+        assert!(!glob.is_match("/other/main.rs"));
+    }
+
+    #[test]
+    fn test_rule_policy_first_match_and_default() {
+        let policy = RulePolicy::new()
+            .with_rule(PermissionRule::allow_once().with_kind("edit").with_path("/work/**"))
+            .with_rule(PermissionRule::reject().with_kind("edit"));
+
+        let allowed = policy.decide(&edit_request("/work/src/main.rs"));
+        assert_eq!(allowed.option_id.as_deref(), Some("allow"));
+
+        let rejected = policy.decide(&edit_request("/etc/passwd"));
+        assert_eq!(rejected.option_id.as_deref(), Some("reject"));
+        assert_eq!(rejected.reason, "matched rule #2");
+
+        let mut run = edit_request("/work/a");
+        run.kind = Some("execute".into());
+        assert_eq!(policy.decide(&run).reason, "no rule matched");
+    }
+
+    #[test]
+    fn test_path_traversal_is_normalized() {
+        let rule = PermissionRule::allow_once().with_path("/work/**");
+        assert!(rule.matches(&edit_request("/work/./src/../lib.rs")));
+        assert!(!rule.matches(&edit_request("/work/../etc/passwd")));
+        assert!(!rule.matches(&edit_request("/../work/main.rs")));
+
+        let relative = PermissionRule::allow_once().with_path("src/**");
+        assert!(relative.matches(&edit_request("src/main.rs")));
+        assert!(!relative.matches(&edit_request("../src/main.rs")));
+        assert!(!relative.matches(&edit_request("src/../../src/main.rs")));
+    }
+
+    #[test]
+    fn test_relative_paths_use_cwd() {
+        let rule = PermissionRule::allow_once().with_path("/work/**");
+        let mut request = edit_request("src/main.rs");
+        assert!(!rule.matches(&request));
+
+        request.cwd = Some("/work".into());
+        assert!(rule.matches(&request));
+        request.paths = vec!["../etc/passwd".into()];
+        assert!(!rule.matches(&request));
+    }
+
+    #[test]
+    fn test_policy_from_config() {
+        let policy: RulePolicy = serde_json::from_value(serde_json::json!({
+            "default": "allow_once",
+            "rules": [{"action": "reject", "kinds": ["edit"], "paths": ["/etc/**"]}],
+        }))
+        .unwrap();
+        assert_eq!(policy.decide(&edit_request("/etc/passwd")).option_id.as_deref(), Some("reject"));
+        assert_eq!(policy.decide(&edit_request("/work/a")).option_id.as_deref(), Some("allow"));
+
+        let invalid = serde_json::json!({"rules": [{"action": "reject", "command": "("}]});
+        assert!(serde_json::from_value::<RulePolicy>(invalid).is_err());
+    }
+
+    #[test]
+    fn test_command_rule() {
+        let rule = PermissionRule::allow_once().with_command(r"^cargo (build|test)\b").unwrap();
+        let mut request = edit_request("/work");
+        request.command = Some("cargo test --all".into());
+        assert!(rule.matches(&request));
+
+        request.command = Some("rm -rf /".into());
+        assert!(!rule.matches(&request));
+        assert!(PermissionRule::reject().with_command("(").is_err());
+    }
+
+    #[test]
+    fn test_option_for_never_escalates() {
+        let request = PermissionRequest {
+            options: vec![option("always", PermissionOptionKind::AllowAlways)],
+            ..Default::default()
+        };
+
+        assert!(request.option_for(PermissionAction::AllowOnce).is_none());
+        assert_eq!(request.option_for(PermissionAction::AllowAlways).unwrap().id, "always");
+        assert!(DenyAll.decide(&request).is_cancelled());
+    }
+}
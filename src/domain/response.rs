@@ -0,0 +1,168 @@
+//! Agent response
+//!
+//! Everything an agent produced while answering a prompt: the answer itself,
+//! its reasoning, the tools it used and its plan.
+
+use std::fmt;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use super::agent::AgentInfo;
+
+/// Why the agent stopped answering
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    /// The agent finished its turn
+    #[default]
+    EndTurn,
+    /// The model hit its token limit
+    MaxTokens,
+    /// The agent hit its limit of model requests in one turn
+    MaxTurnRequests,
+    /// The agent refused to continue
+    Refusal,
+    /// The turn was cancelled by the client
+    Cancelled,
+}
+
+/// Execution status of a tool call
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCallStatus {
+    /// Not started yet (e.g., waiting for permission)
+    #[default]
+    Pending,
+    /// Running
+    InProgress,
+    /// Finished successfully
+    Completed,
+    /// Finished with an error
+    Failed,
+}
+
+/// A file location a tool call touched
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolCallLocation {
+    /// File path
+    pub path: String,
+    /// Line number, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+}
+
+/// A tool the agent invoked
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall {
+    /// Tool call ID (unique within the session)
+    pub id: String,
+    /// Human-readable description of what the tool does
+    pub title: String,
+    /// Tool kind (e.g., "read", "edit", "execute")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// Latest status
+    pub status: ToolCallStatus,
+    /// Files the tool call touched
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locations: Vec<ToolCallLocation>,
+    /// Input the agent passed to the tool
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_input: Option<serde_json::Value>,
+    /// Output the tool produced
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_output: Option<serde_json::Value>,
+}
+
+/// Priority of a plan entry
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanEntryPriority {
+    High,
+    #[default]
+    Medium,
+    Low,
+}
+
+/// Progress of a plan entry
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanEntryStatus {
+    #[default]
+    Pending,
+    InProgress,
+    Completed,
+}
+
+/// A step of the agent's plan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanEntry {
+    /// What the step does
+    pub content: String,
+    /// How important the step is
+    pub priority: PlanEntryPriority,
+    /// Progress of the step
+    pub status: PlanEntryStatus,
+}
+
+/// Tokens the model used for a prompt, as reported by the agent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    /// Tokens read (prompt and context)
+    pub input_tokens: u64,
+    /// Tokens generated
+    pub output_tokens: u64,
+    /// All tokens used
+    pub total_tokens: u64,
+}
+
+/// How long the agent took to answer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResponseTimings {
+    /// Time from sending the prompt to the end of the turn
+    pub total: Duration,
+    /// Time until the first answer text arrived
+    pub first_chunk: Option<Duration>,
+}
+
+/// The agent's answer to a prompt, with what it did along the way
+#[derive(Debug, Clone, Default)]
+pub struct AgentResponse {
+    /// Answer text
+    pub text: String,
+    /// Reasoning the agent shared while answering
+    pub thoughts: String,
+    /// Tools the agent invoked, in order, with their latest status
+    pub tool_calls: Vec<ToolCall>,
+    /// The agent's latest plan
+    pub plan: Vec<PlanEntry>,
+    /// Why the agent stopped
+    pub stop_reason: StopReason,
+    /// How long the answer took
+    pub timings: ResponseTimings,
+    /// Tokens used, if the agent reports them
+    pub usage: Option<TokenUsage>,
+    /// Agent that answered, if known
+    pub agent: Option<AgentInfo>,
+}
+
+impl AgentResponse {
+    /// Create a response carrying only text
+    pub fn from_text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether the turn was cancelled before the agent finished
+    pub fn is_cancelled(&self) -> bool {
+        self.stop_reason == StopReason::Cancelled
+    }
+}
+
+impl fmt::Display for AgentResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
@@ -7,7 +7,10 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::mcp::McpServer;
+use super::content::Prompt;
 use super::message::{Message, Role};
+use super::response::PlanEntry;
 
 /// A conversation session with an agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,8 +26,17 @@ pub struct Session {
     /// System prompt for this session
     #[serde(skip_serializing_if = "Option::is_none")]
     pub system_prompt: Option<String>,
+    /// Model (agent or `agent/mode`) answering this session, when routed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// MCP servers given to this session's ACP session
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mcp_servers: Vec<McpServer>,
     /// Message history
     pub messages: Vec<Message>,
+    /// The agent's latest plan
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plan: Vec<PlanEntry>,
     /// Creation timestamp
     pub created_at: DateTime<Utc>,
     /// Last activity timestamp
@@ -43,7 +55,10 @@ impl Session {
             acp_session_id: None,
             title: None,
             system_prompt: None,
+            model: None,
+            mcp_servers: Vec::new(),
             messages: Vec::new(),
+            plan: Vec::new(),
             created_at: now,
             updated_at: now,
             metadata: HashMap::new(),
@@ -65,6 +80,18 @@ impl Session {
         self
     }
 
+    /// Set the model answering the session
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Give the session's ACP session these MCP servers
+    pub fn with_mcp_servers(mut self, servers: Vec<McpServer>) -> Self {
+        self.mcp_servers = servers;
+        self
+    }
+
     /// Add a message to the session
     pub fn add_message(&mut self, message: Message) {
         self.messages.push(message);
@@ -103,6 +130,12 @@ impl Session {
         self.updated_at = Utc::now();
     }
 
+    /// Replace the agent's plan
+    pub fn set_plan(&mut self, plan: Vec<PlanEntry>) {
+        self.plan = plan;
+        self.updated_at = Utc::now();
+    }
+
     /// Get metadata
     pub fn get_metadata(&self, key: &str) -> Option<&serde_json::Value> {
         self.metadata.get(key)
@@ -123,6 +156,12 @@ impl Session {
             .collect::<Vec<_>>()
             .join("\n\n")
     }
+
+    /// The history as a prompt: the text transcript plus every attachment
+    pub fn history_prompt(&self) -> Prompt {
+        let attachments = self.messages.iter().flat_map(Message::attachments).cloned();
+        Prompt::from(self.build_prompt()).with_parts(attachments)
+    }
 }
 
 impl Default for Session {
@@ -22,15 +22,33 @@ pub enum Error {
     #[error("Timeout waiting for response")]
     Timeout,
 
+    #[error("Request cancelled")]
+    Cancelled,
+
     #[error("Session not found: {0}")]
     SessionNotFound(String),
 
+    #[error("Session busy: a turn is already running in {0}")]
+    SessionBusy(String),
+
     #[error("Not connected")]
     NotConnected,
 
     #[error("Agent not found: {0}")]
     AgentNotFound(String),
 
+    #[error("Invalid configuration: {0}")]
+    Config(String),
+
+    #[error("Access denied: {0}")]
+    AccessDenied(String),
+
+    #[error("Agent does not accept {0} content")]
+    UnsupportedContent(String),
+
+    #[error("Invalid prompt: {0}")]
+    InvalidPrompt(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -64,10 +82,35 @@ impl Error {
         Error::SessionNotFound(id.into())
     }
 
+    /// Create a session busy error
+    pub fn session_busy<S: Into<String>>(id: S) -> Self {
+        Error::SessionBusy(id.into())
+    }
+
     /// Create an agent not found error
     pub fn agent_not_found<S: Into<String>>(name: S) -> Self {
         Error::AgentNotFound(name.into())
     }
+
+    /// Create a configuration error
+    pub fn config<S: Into<String>>(msg: S) -> Self {
+        Error::Config(msg.into())
+    }
+
+    /// Create an access denied error
+    pub fn access_denied<S: Into<String>>(msg: S) -> Self {
+        Error::AccessDenied(msg.into())
+    }
+
+    /// Create an unsupported content error
+    pub fn unsupported_content<S: Into<String>>(modality: S) -> Self {
+        Error::UnsupportedContent(modality.into())
+    }
+
+    /// Create an invalid prompt error
+    pub fn invalid_prompt<S: Into<String>>(msg: S) -> Self {
+        Error::InvalidPrompt(msg.into())
+    }
 }
 
 /// Result type alias for ACP operations
@@ -1,54 +1,390 @@
 //! ACP Connection Management
 //!
-//! Handles spawning agent CLI and managing ACP sessions.
+//! Handles spawning agent CLI and managing long-lived ACP connections.
+//!
+//! The ACP `Client` trait is not `Send`, so every connection runs on its own
+//! worker thread with a current-thread runtime and a `LocalSet`. Callers talk
+//! to the worker through a channel, which keeps `AcpConnection` itself `Send`.
 
-use std::sync::Arc;
-use tokio::process::Command;
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::LocalSet;
+use tokio_util::sync::CancellationToken;
 use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
 use agent_client_protocol as acp;
 use acp::Agent as _;
-use tracing::info;
+use tracing::{debug, info, warn};
 
-use crate::domain::{Agent, AgentConfig};
+use crate::domain::{
+    Agent, AgentCapabilities, AgentConfig, AgentInfo, AuthMethod, ContentPart, McpServer,
+    PermissionPolicy, Prompt,
+};
 use crate::error::{Error, Result};
+use super::fs::Sandbox;
 use super::handler::{AcpClientHandler, ResponseCollector};
+use super::terminal::TerminalManager;
 
-/// ACP connection manager
-pub struct AcpConnection;
+/// How long a cancelled or timed-out prompt may take to wind down
+const CANCEL_GRACE: Duration = Duration::from_secs(10);
 
-impl AcpConnection {
-    /// Run an ACP session with the given agent and prompt
-    pub async fn run_session<A: Agent>(
-        agent: &A,
-        config: &AgentConfig,
-        prompt: &str,
-        collector: Arc<ResponseCollector>,
-    ) -> Result<()> {
-        info!("[ACP] Starting {} acp...", agent.name());
+/// How long to wait for pending updates to be handled after a response
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
 
-        // Build command
-        let mut cmd = Command::new(agent.cli_path());
-        for arg in agent.acp_args() {
-            cmd.arg(arg);
-        }
+/// Longest wait for `authenticate`, so a login that needs a browser fails fast
+const AUTH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Everything needed to launch an agent process
+///
+/// Captured from an `Agent` and its `AgentConfig` so connections can be
+/// started on worker threads without holding on to the agent itself.
+#[derive(Debug, Clone)]
+pub struct LaunchSpec {
+    /// Agent name (used for logging and thread names)
+    pub name: String,
+    /// Path to the CLI executable
+    pub cli_path: String,
+    /// Full argument list for ACP mode
+    pub args: Vec<String>,
+    /// Extra environment variables
+    pub env: Vec<(String, String)>,
+    /// Working directory for the agent process and its sessions
+    pub working_dir: Option<String>,
+    /// Longest wait for the agent to report its first session ready
+    pub session_init_delay: Duration,
+    /// Extra delay after a prompt's updates have been drained (fallback for odd agents)
+    pub post_prompt_delay: Duration,
+    /// Timeout for a single prompt
+    pub timeout: Duration,
+    /// Policy answering the agent's permission requests
+    pub permission_policy: Arc<dyn PermissionPolicy>,
+    /// Commands the agent may run in client-side terminals
+    pub terminal_commands: Vec<String>,
+    /// MCP servers given to every session
+    pub mcp_servers: Vec<McpServer>,
+    /// Largest file embedded in a prompt
+    pub max_resource_size: u64,
+    /// Authentication methods to use, in order of preference
+    pub auth_methods: Vec<String>,
+}
+
+impl LaunchSpec {
+    /// Build a launch spec from an agent and its configuration
+    pub fn new<A: Agent + ?Sized>(agent: &A, config: &AgentConfig) -> Self {
+        let mut args = agent.acp_args();
 
         // Add agent mode if specified
         if let Some(ref mode) = config.agent_mode {
-            cmd.args(["--agent", mode]);
+            args.extend(agent.mode_args(mode));
         }
 
         // Add extra args
-        for arg in &config.extra_args {
-            cmd.arg(arg);
+        args.extend(config.extra_args.iter().cloned());
+
+        Self {
+            name: agent.name().to_string(),
+            cli_path: agent.cli_path().to_string(),
+            args,
+            env: agent.environment(),
+            working_dir: config.working_dir.clone(),
+            session_init_delay: config.session_init_delay.unwrap_or_else(|| agent.session_init_delay()),
+            post_prompt_delay: config.post_prompt_delay.unwrap_or_else(|| agent.post_prompt_delay()),
+            timeout: config.timeout,
+            permission_policy: config.permission_policy.clone(),
+            terminal_commands: config.terminal_commands.clone(),
+            mcp_servers: config.mcp_servers.clone(),
+            max_resource_size: config.max_resource_size,
+            auth_methods: agent.auth_methods(),
         }
+    }
+
+    /// Directory that files attached to prompts are read from
+    ///
+    /// Only a configured `working_dir`; the server's own directory is never exposed.
+    fn attachment_root(&self) -> Result<&str> {
+        self.working_dir
+            .as_deref()
+            .ok_or_else(|| Error::invalid_prompt("File attachments need a configured working directory"))
+    }
+
+    /// Working directory sent with new sessions
+    fn cwd(&self) -> PathBuf {
+        self.working_dir
+            .clone()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| std::env::current_dir().unwrap_or_default())
+    }
+}
+
+/// Work sent from an `AcpConnection` handle to its worker thread
+enum Job {
+    Prompt {
+        /// Existing ACP session to prompt in; a new one is created when `None`
+        session_id: Option<String>,
+        /// MCP servers for a new session, on top of the configured ones
+        mcp_servers: Vec<McpServer>,
+        prompt: Prompt,
+        collector: Arc<ResponseCollector>,
+        cancel: CancellationToken,
+        reply: oneshot::Sender<Result<String>>,
+    },
+    Load {
+        session_id: String,
+        mcp_servers: Vec<McpServer>,
+        reply: oneshot::Sender<Result<()>>,
+    },
+}
+
+/// A long-lived, initialized ACP connection to an agent process
+///
+/// Dropping the handle shuts down the worker thread and kills the process.
+pub struct AcpConnection {
+    id: u64,
+    jobs: mpsc::UnboundedSender<Job>,
+    alive: Arc<AtomicBool>,
+    /// ACP sessions kept open on this process for follow-up turns
+    sessions: Mutex<HashSet<String>>,
+    /// Agent identity reported during `initialize`
+    info: AgentInfo,
+    /// Capabilities from `initialize`, plus modes and models from `session/new`
+    capabilities: Arc<Mutex<AgentCapabilities>>,
+}
+
+impl AcpConnection {
+    /// Spawn the agent process on a new worker thread and run `initialize`
+    pub async fn start(spec: LaunchSpec) -> Result<Self> {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+        let (jobs_tx, jobs_rx) = mpsc::unbounded_channel();
+        let (ready_tx, ready_rx) = oneshot::channel();
+        let alive = Arc::new(AtomicBool::new(true));
+        let worker_alive = alive.clone();
+        let capabilities = Arc::new(Mutex::new(AgentCapabilities::default()));
+        let worker_capabilities = capabilities.clone();
+
+        std::thread::Builder::new()
+            .name(format!("acp-{}-{}", spec.name, id))
+            .spawn(move || Worker::run(spec, jobs_rx, ready_tx, worker_alive, worker_capabilities))
+            .map_err(|e| Error::spawn(e.to_string()))?;
+
+        let info = ready_rx
+            .await
+            .map_err(|_| Error::connection("ACP worker exited during startup"))??;
+
+        info!("[ACP] Connection {} ready", id);
+        Ok(Self {
+            id,
+            jobs: jobs_tx,
+            alive,
+            sessions: Mutex::new(HashSet::new()),
+            info,
+            capabilities,
+        })
+    }
+
+    /// Connection identifier (unique within the process)
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Whether the agent process and worker thread are still running
+    pub fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::SeqCst) && !self.jobs.is_closed()
+    }
+
+    /// Whether this connection hosts the given ACP session
+    pub fn hosts(&self, session_id: &str) -> bool {
+        self.sessions.lock().unwrap().contains(session_id)
+    }
+
+    /// IDs of the ACP sessions hosted by this connection
+    pub fn session_ids(&self) -> Vec<String> {
+        self.sessions.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Run a prompt in a fresh, one-off ACP session, collecting updates into `collector`
+    ///
+    /// Cancelling `cancel` sends `session/cancel`; the partial response stays
+    /// in the collector.
+    pub async fn prompt(
+        &self,
+        prompt: &Prompt,
+        collector: Arc<ResponseCollector>,
+        cancel: CancellationToken,
+    ) -> Result<()> {
+        self.run(None, Vec::new(), prompt, collector, cancel).await.map(|_| ())
+    }
+
+    /// Run a prompt in a persistent ACP session and return its ID
+    ///
+    /// Continues `session_id` when this connection hosts it, otherwise starts a
+    /// new session with `mcp_servers` that stays open for follow-up turns.
+    pub async fn session_prompt(
+        &self,
+        session_id: Option<&str>,
+        mcp_servers: &[McpServer],
+        prompt: &Prompt,
+        collector: Arc<ResponseCollector>,
+        cancel: CancellationToken,
+    ) -> Result<String> {
+        let session_id = session_id.filter(|id| self.hosts(id));
+        let acp_session_id = self.run(session_id.map(String::from), mcp_servers.to_vec(), prompt, collector, cancel).await?;
+        self.sessions.lock().unwrap().insert(acp_session_id.clone());
+        Ok(acp_session_id)
+    }
+
+    /// Agent identity reported during `initialize`
+    pub fn info(&self) -> &AgentInfo {
+        &self.info
+    }
+
+    /// Capabilities advertised by the agent
+    ///
+    /// Modes and models are only known once a session has been created.
+    pub fn capabilities(&self) -> AgentCapabilities {
+        self.capabilities.lock().unwrap().clone()
+    }
+
+    /// Whether the agent supports `session/load`
+    pub fn supports_load_session(&self) -> bool {
+        self.capabilities.lock().unwrap().load_session
+    }
+
+    /// Resume a session this process doesn't host with `session/load`
+    ///
+    /// The history the agent replays while loading is discarded; the caller
+    /// already has it. On success the session is hosted by this connection.
+    pub async fn load_session(&self, session_id: &str, mcp_servers: &[McpServer]) -> Result<()> {
+        if !self.supports_load_session() {
+            return Err(Error::session("Agent does not support session/load"));
+        }
+
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.jobs
+            .send(Job::Load {
+                session_id: session_id.to_string(),
+                mcp_servers: mcp_servers.to_vec(),
+                reply: reply_tx,
+            })
+            .map_err(|_| Error::NotConnected)?;
+
+        reply_rx
+            .await
+            .map_err(|_| Error::connection("ACP worker exited"))??;
+
+        self.sessions.lock().unwrap().insert(session_id.to_string());
+        Ok(())
+    }
+
+    /// Forget a hosted ACP session so it is no longer continued
+    pub fn forget(&self, session_id: &str) {
+        self.sessions.lock().unwrap().remove(session_id);
+    }
+
+    /// Send a prompt job to the worker and wait for the session ID it ran in
+    ///
+    /// Prompts with content the agent doesn't accept are refused up front.
+    async fn run(
+        &self,
+        session_id: Option<String>,
+        mcp_servers: Vec<McpServer>,
+        prompt: &Prompt,
+        collector: Arc<ResponseCollector>,
+        cancel: CancellationToken,
+    ) -> Result<String> {
+        prompt.check_supported(&self.capabilities())?;
+
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.jobs
+            .send(Job::Prompt {
+                session_id,
+                mcp_servers,
+                prompt: prompt.clone(),
+                collector,
+                cancel,
+                reply: reply_tx,
+            })
+            .map_err(|_| Error::NotConnected)?;
+
+        reply_rx
+            .await
+            .map_err(|_| Error::connection("ACP worker exited"))?
+    }
+}
+
+/// State owned by a connection's worker thread
+struct Worker {
+    spec: LaunchSpec,
+    conn: acp::ClientSideConnection,
+    handler: Rc<AcpClientHandler>,
+    alive: Arc<AtomicBool>,
+    capabilities: Arc<Mutex<AgentCapabilities>>,
+    sessions_created: usize,
+    _child: Child,
+}
+
+impl Worker {
+    /// Thread entry point: connect, report readiness, then serve jobs
+    fn run(
+        spec: LaunchSpec,
+        jobs: mpsc::UnboundedReceiver<Job>,
+        ready: oneshot::Sender<Result<AgentInfo>>,
+        alive: Arc<AtomicBool>,
+        capabilities: Arc<Mutex<AgentCapabilities>>,
+    ) {
+        let rt = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+            Ok(rt) => rt,
+            Err(e) => {
+                alive.store(false, Ordering::SeqCst);
+                let _ = ready.send(Err(Error::spawn(e.to_string())));
+                return;
+            }
+        };
+
+        let local = LocalSet::new();
+        let worker_alive = alive.clone();
+        local.block_on(&rt, async move {
+            match Worker::connect(spec, worker_alive, capabilities).await {
+                Ok((worker, info)) => {
+                    let _ = ready.send(Ok(info));
+                    worker.serve(jobs).await;
+                }
+                Err(e) => {
+                    let _ = ready.send(Err(e));
+                }
+            }
+        });
+
+        alive.store(false, Ordering::SeqCst);
+    }
+
+    /// Spawn the agent process and run the `initialize` handshake
+    async fn connect(
+        spec: LaunchSpec,
+        alive: Arc<AtomicBool>,
+        capabilities: Arc<Mutex<AgentCapabilities>>,
+    ) -> Result<(Self, AgentInfo)> {
+        info!("[ACP] Starting {} acp...", spec.name);
+
+        // Build command
+        let mut cmd = Command::new(&spec.cli_path);
+        cmd.args(&spec.args);
 
         // Add environment variables
-        for (key, value) in agent.environment() {
+        for (key, value) in &spec.env {
             cmd.env(key, value);
         }
 
         // Set working directory
-        if let Some(ref dir) = config.working_dir {
+        if let Some(ref dir) = spec.working_dir {
             cmd.current_dir(dir);
         }
 
@@ -65,80 +401,569 @@ impl AcpConnection {
         let stdout = child.stdout.take()
             .ok_or_else(|| Error::connection("Failed to get stdout"))?;
 
+        // Drain stderr so a chatty agent can't block on a full pipe
+        if let Some(stderr) = child.stderr.take() {
+            let name = spec.name.clone();
+            tokio::task::spawn_local(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    debug!("[ACP] {} stderr: {}", name, line);
+                }
+            });
+        }
+
         let outgoing = stdin.compat_write();
         let incoming = stdout.compat();
 
-        let handler = AcpClientHandler::new(collector);
+        let mut handler = AcpClientHandler::new()
+            .with_permission_policy(spec.permission_policy.clone())
+            .with_cwd(spec.cwd());
+
+        // File system access is confined to the working directory
+        if let Some(ref dir) = spec.working_dir {
+            match Sandbox::new(dir) {
+                Ok(sandbox) => handler = handler.with_sandbox(sandbox),
+                Err(e) => warn!("[ACP] File system access disabled for {}: {}", dir, e),
+            }
+        }
+        let fs_enabled = handler.has_fs();
+
+        // Terminals are only offered when some commands are allowed
+        if !spec.terminal_commands.is_empty() {
+            match TerminalManager::new(spec.terminal_commands.clone(), spec.cwd()) {
+                Ok(terminals) => handler = handler.with_terminals(terminals),
+                Err(e) => warn!("[ACP] Terminals disabled: {}", e),
+            }
+        }
+        let terminal_enabled = handler.has_terminals();
+        let handler = Rc::new(handler);
 
         // Create ACP connection
         let (conn, handle_io) = acp::ClientSideConnection::new(
-            handler,
+            handler.clone(),
             outgoing,
             incoming,
-            |fut| {
-                tokio::task::spawn_local(fut);
+            {
+                // The first future is the connection's dispatch loop; every
+                // later one handles a single incoming request or notification
+                let tracker = handler.clone();
+                let dispatch_spawned = Cell::new(false);
+                move |fut| {
+                    if dispatch_spawned.replace(true) {
+                        tracker.spawn_tracked(fut);
+                    } else {
+                        tokio::task::spawn_local(fut);
+                    }
+                }
             },
         );
 
-        // Handle I/O in the background
-        tokio::task::spawn_local(handle_io);
+        // Handle I/O in the background; the connection is dead once it stops
+        let io_alive = alive.clone();
+        tokio::task::spawn_local(async move {
+            if let Err(e) = handle_io.await {
+                warn!("[ACP] I/O error: {:?}", e);
+            }
+            io_alive.store(false, Ordering::SeqCst);
+        });
 
         // Initialize
         info!("[ACP] Initializing...");
         let client_info = acp::Implementation::new("acp-client", env!("CARGO_PKG_VERSION"))
             .title("ACP Client");
+        let fs = acp::FileSystemCapability::new()
+            .read_text_file(fs_enabled)
+            .write_text_file(fs_enabled);
         let init_request = acp::InitializeRequest::new(acp::ProtocolVersion::LATEST)
+            .client_capabilities(acp::ClientCapabilities::new().fs(fs).terminal(terminal_enabled))
             .client_info(client_info);
 
-        let init_response = conn.initialize(init_request)
+        let init_response = tokio::time::timeout(spec.timeout, conn.initialize(init_request))
             .await
+            .map_err(|_| Error::Timeout)?
             .map_err(|e| Error::protocol(format!("Initialize failed: {:?}", e)))?;
 
         info!("[ACP] Initialized: {:?}", init_response.agent_info);
 
-        // Create session
+        let info = agent_info(&spec.name, init_response.agent_info);
+        *capabilities.lock().unwrap() = agent_capabilities(
+            &init_response.agent_capabilities,
+            &init_response.auth_methods,
+        );
+
+        // Some agents (e.g., Gemini) refuse to create sessions until the client authenticates
+        if let Some(method) = auth_method(&spec.auth_methods, &init_response.auth_methods) {
+            info!("[ACP] Authenticating with {}", method);
+            let authenticate = conn.authenticate(acp::AuthenticateRequest::new(method.clone()));
+            tokio::time::timeout(spec.timeout.min(AUTH_TIMEOUT), authenticate)
+                .await
+                .map_err(|_| Error::connection(format!("Authentication with {} timed out", method)))?
+                .map_err(|e| Error::connection(format!("Authentication with {} failed: {:?}", method, e)))?;
+        }
+
+        let worker = Self {
+            spec,
+            conn,
+            handler,
+            alive,
+            capabilities,
+            sessions_created: 0,
+            _child: child,
+        };
+        Ok((worker, info))
+    }
+
+    /// Process jobs until the handle is dropped or the agent dies
+    async fn serve(mut self, mut jobs: mpsc::UnboundedReceiver<Job>) {
+        while let Some(job) = jobs.recv().await {
+            match job {
+                Job::Prompt { session_id, mcp_servers, prompt, collector, cancel, reply } => {
+                    let result = self.prompt(session_id, &mcp_servers, &prompt, collector, cancel).await;
+                    let _ = reply.send(result);
+                }
+                Job::Load { session_id, mcp_servers, reply } => {
+                    let result = self.load_session(session_id, &mcp_servers).await;
+                    let _ = reply.send(result);
+                }
+            }
+
+            if !self.alive.load(Ordering::SeqCst) {
+                break;
+            }
+        }
+
+        info!("[ACP] {} connection closed", self.spec.name);
+    }
+
+    /// Send a prompt in `session_id`, creating a new session when `None`
+    async fn prompt(
+        &mut self,
+        session_id: Option<String>,
+        mcp_servers: &[McpServer],
+        prompt: &Prompt,
+        collector: Arc<ResponseCollector>,
+        cancel: CancellationToken,
+    ) -> Result<String> {
+        if cancel.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+
+        let resolved;
+        let prompt = if prompt.has_files() {
+            resolved = self.resolve_files(prompt).await?;
+            &resolved
+        } else {
+            prompt
+        };
+
+        let session_id = match session_id {
+            Some(id) => {
+                info!("[ACP] Continuing session: {}", id);
+                acp::SessionId::new(id)
+            }
+            None => self.new_session(mcp_servers).await?,
+        };
+
+        self.handler.attach(session_id.clone(), collector.clone());
+        let result = self.send_prompt(session_id.clone(), prompt, &cancel).await;
+        self.handler.detach(&session_id);
+
+        let result = result.map(|stop_reason| collector.set_stop_reason(stop_reason));
+
+        result.map(|_| session_id.to_string())
+    }
+
+    /// Load an existing session, discarding the history the agent replays
+    async fn load_session(&mut self, session_id: String, mcp_servers: &[McpServer]) -> Result<()> {
+        info!("[ACP] Loading session: {}", session_id);
+        let session_id = acp::SessionId::new(session_id);
+        let load_request = acp::LoadSessionRequest::new(session_id.clone(), self.spec.cwd())
+            .mcp_servers(self.mcp_servers(mcp_servers));
+
+        // Replayed updates go to a throwaway collector
+        self.handler.attach(session_id.clone(), Arc::new(ResponseCollector::new()));
+        let result = tokio::time::timeout(self.spec.timeout, self.conn.load_session(load_request)).await;
+        // Replay notifications sent before the response go to the throwaway collector too
+        self.drain().await;
+        self.handler.detach(&session_id);
+
+        result
+            .map_err(|_| Error::Timeout)?
+            .map_err(|e| Error::session(format!("Session load failed: {:?}", e)))?;
+
+        info!("[ACP] Session loaded: {:?}", session_id);
+        Ok(())
+    }
+
+    /// Create a new ACP session
+    async fn new_session(&mut self, mcp_servers: &[McpServer]) -> Result<acp::SessionId> {
         info!("[ACP] Creating session...");
-        let cwd = config.working_dir
-            .clone()
-            .map(std::path::PathBuf::from)
-            .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
-        let session_request = acp::NewSessionRequest::new(cwd);
 
-        let session_response = conn.new_session(session_request)
+        // Only the first session on a process waits for agent warm-up (e.g., MCP servers)
+        let wait_ready = self.sessions_created == 0 && !self.spec.session_init_delay.is_zero();
+        if wait_ready {
+            self.handler.expect_ready();
+        }
+
+        let session_request = acp::NewSessionRequest::new(self.spec.cwd())
+            .mcp_servers(self.mcp_servers(mcp_servers));
+
+        let session_response = self.conn.new_session(session_request)
             .await
             .map_err(|e| Error::session(format!("Session creation failed: {:?}", e)))?;
 
         let session_id = session_response.session_id;
         info!("[ACP] Session created: {:?}", session_id);
 
-        // Wait for agent to initialize (e.g., MCP servers)
-        let init_delay = agent.session_init_delay();
-        if !init_delay.is_zero() {
-            tokio::time::sleep(init_delay).await;
+        // Modes and models are only reported per session
+        {
+            let mut capabilities = self.capabilities.lock().unwrap();
+            if let Some(modes) = session_response.modes {
+                capabilities.available_modes = modes.available_modes.into_iter().map(|m| m.id.to_string()).collect();
+                capabilities.current_mode = Some(modes.current_mode_id.to_string());
+            }
+            if let Some(models) = session_response.models {
+                capabilities.available_models = models.available_models.into_iter().map(|m| m.model_id.to_string()).collect();
+                capabilities.current_model = Some(models.current_model_id.to_string());
+            }
+        }
+
+        if wait_ready {
+            let ready_timeout = self.spec.session_init_delay;
+            if !self.handler.wait_ready(&session_id, ready_timeout).await {
+                debug!("[ACP] No readiness signal within {:?}, continuing", ready_timeout);
+            }
+        }
+        self.sessions_created += 1;
+
+        Ok(session_id)
+    }
+
+    /// Wait for incoming messages received so far to be handled
+    async fn drain(&self) {
+        if tokio::time::timeout(DRAIN_TIMEOUT, self.handler.drained()).await.is_err() {
+            warn!("[ACP] Pending updates not handled within {:?}", DRAIN_TIMEOUT);
+        }
+    }
+
+    /// Configured plus per-session MCP servers, minus transports the agent can't use
+    fn mcp_servers(&self, extra: &[McpServer]) -> Vec<acp::McpServer> {
+        let capabilities = self.capabilities.lock().unwrap();
+        self.spec
+            .mcp_servers
+            .iter()
+            .chain(extra)
+            .filter(|server| {
+                let supported = match server {
+                    McpServer::Stdio { .. } => true,
+                    McpServer::Http { .. } => capabilities.mcp_http,
+                    McpServer::Sse { .. } => capabilities.mcp_sse,
+                };
+                if !supported {
+                    warn!("[ACP] Skipping MCP server {}: agent does not support {}", server.name(), server.transport());
+                }
+                supported
+            })
+            .map(to_acp_mcp_server)
+            .collect()
+    }
+
+    /// Replace the files a prompt references with their content
+    ///
+    /// Text files are embedded when the agent accepts embedded resources;
+    /// other files, and every file for agents that don't, are sent as links.
+    async fn resolve_files(&self, prompt: &Prompt) -> Result<Prompt> {
+        let sandbox = Sandbox::new(self.spec.attachment_root()?)?;
+        let embed = self.capabilities.lock().unwrap().embedded_context;
+
+        let mut parts = Vec::with_capacity(prompt.parts().len());
+        for part in prompt.parts() {
+            let ContentPart::File { path } = part else {
+                parts.push(part.clone());
+                continue;
+            };
+
+            let local = path.strip_prefix("file://").or_else(|| path.strip_prefix("file:")).unwrap_or(path);
+            let file = sandbox
+                .read_resource(Path::new(local), self.spec.max_resource_size)
+                .await
+                .map_err(|e| Error::invalid_prompt(format!("Cannot attach {}: {}", path, e)))?;
+
+            parts.push(match file.text {
+                Some(text) if embed => ContentPart::Resource {
+                    uri: file.uri,
+                    mime_type: Some(file.mime_type),
+                    text,
+                },
+                _ => ContentPart::ResourceLink {
+                    uri: file.uri,
+                    name: file.name,
+                    mime_type: Some(file.mime_type),
+                    size: Some(file.size),
+                },
+            });
         }
 
-        // Send prompt with timeout
-        info!("[ACP] Sending prompt ({} chars)...", prompt.len());
-        let content = vec![acp::ContentBlock::Text(acp::TextContent::new(prompt.to_string()))];
-        let prompt_request = acp::PromptRequest::new(session_id, content);
+        Ok(Prompt::new(parts))
+    }
+
+    /// Send a prompt in an existing session and wait for it to finish
+    ///
+    /// Cancellation and timeouts send `session/cancel` and give the agent a
+    /// short grace period to stop; a process that doesn't is not reused.
+    async fn send_prompt(
+        &mut self,
+        session_id: acp::SessionId,
+        prompt: &Prompt,
+        cancel: &CancellationToken,
+    ) -> Result<acp::StopReason> {
+        info!("[ACP] Sending prompt ({} chars, {} attachments)...", prompt.text().len(), prompt.attachments().count());
+        let content = prompt.parts().iter().map(to_content_block).collect();
+        let prompt_request = acp::PromptRequest::new(session_id.clone(), content);
 
         let prompt_start = std::time::Instant::now();
-        let prompt_response = tokio::time::timeout(
-            config.timeout,
-            conn.prompt(prompt_request)
-        ).await
-        .map_err(|_| Error::Timeout)?
-        .map_err(|e| Error::protocol(format!("Prompt failed: {:?}", e)))?;
+        let response = self.conn.prompt(prompt_request);
+        tokio::pin!(response);
+        let deadline = tokio::time::sleep(self.spec.timeout);
+        tokio::pin!(deadline);
+        let mut cancel_sent = false;
+        let mut timed_out = false;
+
+        let prompt_response = loop {
+            tokio::select! {
+                response = &mut response => {
+                    break response.map_err(|e| Error::protocol(format!("Prompt failed: {:?}", e)))?;
+                }
+                _ = cancel.cancelled(), if !cancel_sent => {
+                    info!("[ACP] Cancelling prompt in session {}", session_id);
+                }
+                _ = &mut deadline => {
+                    if cancel_sent {
+                        // The agent ignored the cancellation; don't reuse the process
+                        self.alive.store(false, Ordering::SeqCst);
+                        return Err(Error::Timeout);
+                    }
+                    warn!("[ACP] Prompt timed out, cancelling session {}", session_id);
+                    timed_out = true;
+                }
+            }
+
+            // Reached only on cancellation or timeout
+            cancel_sent = true;
+            if let Err(e) = self.conn.cancel(acp::CancelNotification::new(session_id.clone())).await {
+                warn!("[ACP] Failed to send cancel: {:?}", e);
+            }
+            deadline.as_mut().reset(tokio::time::Instant::now() + CANCEL_GRACE);
+        };
 
         info!("[ACP] Prompt completed in {:?}: {:?}", prompt_start.elapsed(), prompt_response.stop_reason);
 
-        // Give time for final messages
-        let post_delay = agent.post_prompt_delay();
+        // Updates sent before the response must reach the collector first
+        self.drain().await;
+        let post_delay = self.spec.post_prompt_delay;
         if !post_delay.is_zero() {
             tokio::time::sleep(post_delay).await;
         }
 
+        if timed_out {
+            return Err(Error::Timeout);
+        }
+
         info!("[ACP] Session completed");
-        Ok(())
+        Ok(prompt_response.stop_reason)
+    }
+}
+
+/// Build the domain view of an agent's `initialize` identity
+fn agent_info(fallback_name: &str, implementation: Option<acp::Implementation>) -> AgentInfo {
+    match implementation {
+        Some(implementation) => AgentInfo {
+            name: implementation.name,
+            title: implementation.title,
+            version: Some(implementation.version),
+            description: None,
+        },
+        None => AgentInfo {
+            name: fallback_name.to_string(),
+            ..Default::default()
+        },
+    }
+}
+
+/// Build the domain view of an agent's `initialize` capabilities
+fn agent_capabilities(
+    capabilities: &acp::AgentCapabilities,
+    auth_methods: &[acp::AuthMethod],
+) -> AgentCapabilities {
+    AgentCapabilities {
+        load_session: capabilities.load_session,
+        image: capabilities.prompt_capabilities.image,
+        audio: capabilities.prompt_capabilities.audio,
+        embedded_context: capabilities.prompt_capabilities.embedded_context,
+        mcp_http: capabilities.mcp_capabilities.http,
+        mcp_sse: capabilities.mcp_capabilities.sse,
+        auth_methods: auth_methods
+            .iter()
+            .map(|method| AuthMethod {
+                id: method.id.to_string(),
+                name: method.name.clone(),
+                description: method.description.clone(),
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// The first preferred authentication method the agent offers
+fn auth_method(preferred: &[String], offered: &[acp::AuthMethod]) -> Option<String> {
+    preferred
+        .iter()
+        .find(|id| offered.iter().any(|method| method.id.to_string() == **id))
+        .cloned()
+}
+
+/// Convert a domain content part to an ACP content block
+fn to_content_block(part: &ContentPart) -> acp::ContentBlock {
+    match part {
+        ContentPart::Text { text } => acp::ContentBlock::Text(acp::TextContent::new(text)),
+        ContentPart::Image { data, mime_type } => {
+            acp::ContentBlock::Image(acp::ImageContent::new(data, mime_type))
+        }
+        ContentPart::Audio { data, mime_type } => {
+            acp::ContentBlock::Audio(acp::AudioContent::new(data, mime_type))
+        }
+        ContentPart::Resource { uri, mime_type, text } => {
+            let contents = acp::TextResourceContents::new(text, uri).mime_type(mime_type.clone());
+            acp::ContentBlock::Resource(acp::EmbeddedResource::new(
+                acp::EmbeddedResourceResource::TextResourceContents(contents),
+            ))
+        }
+        ContentPart::ResourceLink { uri, name, mime_type, size } => acp::ContentBlock::ResourceLink(
+            acp::ResourceLink::new(name, uri)
+                .mime_type(mime_type.clone())
+                .size(size.map(|size| size as i64)),
+        ),
+        // Files are read before sending; pass any leftover one by reference
+        ContentPart::File { path } => acp::ContentBlock::ResourceLink(acp::ResourceLink::new(path, path)),
+    }
+}
+
+/// Convert a domain MCP server definition to its ACP form
+fn to_acp_mcp_server(server: &McpServer) -> acp::McpServer {
+    let headers = |headers: &std::collections::BTreeMap<String, String>| {
+        headers.iter().map(|(name, value)| acp::HttpHeader::new(name, value)).collect()
+    };
+
+    match server {
+        McpServer::Stdio { name, command, args, env } => acp::McpServer::Stdio(
+            acp::McpServerStdio::new(name, command)
+                .args(args.clone())
+                .env(env.iter().map(|(name, value)| acp::EnvVariable::new(name, value)).collect()),
+        ),
+        McpServer::Http { name, url, headers: h } => {
+            acp::McpServer::Http(acp::McpServerHttp::new(name, url).headers(headers(h)))
+        }
+        McpServer::Sse { name, url, headers: h } => {
+            acp::McpServer::Sse(acp::McpServerSse::new(name, url).headers(headers(h)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::KiroAgent;
+
+    #[test]
+    fn test_launch_spec_args() {
+        let agent = KiroAgent::with_cli_path("kiro-cli");
+        let config = AgentConfig::new("kiro-cli")
+            .with_mode("amzn-builder")
+            .with_args(vec!["--verbose".to_string()]);
+
+        let spec = LaunchSpec::new(&agent, &config);
+        assert_eq!(spec.cli_path, "kiro-cli");
+        assert_eq!(spec.args, vec!["acp", "--agent", "amzn-builder", "--verbose"]);
+    }
+
+    #[test]
+    fn test_attachment_root() {
+        let agent = KiroAgent::with_cli_path("kiro-cli");
+        let spec = LaunchSpec::new(&agent, &AgentConfig::default());
+        assert!(matches!(spec.attachment_root(), Err(Error::InvalidPrompt(_))));
+
+        let spec = LaunchSpec::new(&agent, &AgentConfig::default().with_working_dir("/work"));
+        assert_eq!(spec.attachment_root().unwrap(), "/work");
+    }
+
+    #[tokio::test]
+    async fn test_start_missing_cli() {
+        let agent = KiroAgent::with_cli_path("/nonexistent/acp-agent");
+        let spec = LaunchSpec::new(&agent, &AgentConfig::default());
+
+        let result = AcpConnection::start(spec).await;
+        assert!(matches!(result, Err(Error::Spawn(_))));
+    }
+
+    #[test]
+    fn test_agent_capabilities_from_initialize() {
+        let capabilities = acp::AgentCapabilities::new()
+            .load_session(true)
+            .prompt_capabilities(acp::PromptCapabilities::new().image(true).embedded_context(true))
+            .mcp_capabilities(acp::McpCapabilities::new().http(true));
+        let auth = vec![acp::AuthMethod::new("api-key", "API key")];
+
+        let caps = agent_capabilities(&capabilities, &auth);
+        assert!(caps.load_session && caps.image && caps.embedded_context && caps.mcp_http);
+        assert!(!caps.audio && !caps.mcp_sse);
+        assert_eq!(caps.auth_methods[0].id, "api-key");
+
+        let info = agent_info("kiro", None);
+        assert_eq!(info.name, "kiro");
+        assert!(info.version.is_none());
+    }
+
+    #[test]
+    fn test_auth_method() {
+        let offered = vec![
+            acp::AuthMethod::new("oauth-personal", "Log in with Google"),
+            acp::AuthMethod::new("gemini-api-key", "Use Gemini API key"),
+        ];
+        let preferred = vec!["vertex-ai".to_string(), "gemini-api-key".to_string(), "oauth-personal".to_string()];
+
+        assert_eq!(auth_method(&preferred, &offered).as_deref(), Some("gemini-api-key"));
+        assert_eq!(auth_method(&[], &offered), None);
+        assert_eq!(auth_method(&preferred[..1], &offered), None);
+    }
+
+    #[test]
+    fn test_mcp_server_to_acp() {
+        let server = McpServer::stdio("tools", "tools-mcp", vec!["--stdio".into()]).with_env("TOKEN", "x");
+        let json = serde_json::to_value(to_acp_mcp_server(&server)).unwrap();
+        assert_eq!(json["command"], "tools-mcp");
+        assert_eq!(json["env"][0]["name"], "TOKEN");
+
+        let server = McpServer::http("search", "http://localhost:9000/mcp").with_header("Authorization", "Bearer t");
+        let json = serde_json::to_value(to_acp_mcp_server(&server)).unwrap();
+        assert_eq!(json["type"], "http");
+        assert_eq!(json["headers"][0]["value"], "Bearer t");
+    }
+
+    #[test]
+    fn test_content_to_acp() {
+        let part = ContentPart::resource("file:///work/notes.md", "# Notes");
+        let json = serde_json::to_value(to_content_block(&part)).unwrap();
+        assert_eq!(json["type"], "resource");
+        assert_eq!(json["resource"]["text"], "# Notes");
+
+        let part = ContentPart::ResourceLink {
+            uri: "file:///work/logo.png".into(),
+            name: "logo.png".into(),
+            mime_type: Some("image/png".into()),
+            size: Some(5),
+        };
+        let json = serde_json::to_value(to_content_block(&part)).unwrap();
+        assert_eq!(json["type"], "resource_link");
+        assert_eq!((json["mimeType"].as_str(), json["size"].as_i64()), (Some("image/png"), Some(5)));
     }
 }
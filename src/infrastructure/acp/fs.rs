@@ -0,0 +1,249 @@
+//! Sandboxed file system access
+//!
+//! Backs the ACP `fs/read_text_file` and `fs/write_text_file` client methods,
+//! confining the agent to a single root directory.
+
+use std::path::{Component, Path, PathBuf};
+use tracing::info;
+
+use crate::error::{Error, Result};
+
+/// A file read for attaching to a prompt
+#[derive(Debug, Clone)]
+pub struct FileResource {
+    /// `file://` URI of the file
+    pub uri: String,
+    /// File name
+    pub name: String,
+    /// MIME type guessed from the extension
+    pub mime_type: String,
+    /// File size in bytes
+    pub size: u64,
+    /// File content, for UTF-8 files no larger than the size limit
+    pub text: Option<String>,
+}
+
+/// A directory tree the agent may read and write
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    /// Canonical root directory
+    root: PathBuf,
+    /// Root as configured, for agents that report non-canonical paths
+    alias: PathBuf,
+}
+
+impl Sandbox {
+    /// Create a sandbox rooted at an existing directory
+    pub fn new(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref();
+        let canonical = root.canonicalize()?;
+        if !canonical.is_dir() {
+            return Err(Error::config(format!("Not a directory: {}", root.display())));
+        }
+
+        let alias = normalize(&std::path::absolute(root)?);
+        Ok(Self { root: canonical, alias })
+    }
+
+    /// Root directory of the sandbox
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Resolve a requested path to a location inside the sandbox
+    ///
+    /// Relative paths are taken relative to the root. Paths that leave the root
+    /// or pass through a symlink are rejected; missing components are allowed
+    /// so new files can be written.
+    pub fn resolve(&self, path: &Path) -> Result<PathBuf> {
+        let requested = normalize(&self.root.join(path));
+        let relative = requested
+            .strip_prefix(&self.root)
+            .or_else(|_| requested.strip_prefix(&self.alias))
+            .map_err(|_| Error::access_denied(format!("{} is outside {}", path.display(), self.root.display())))?;
+
+        let mut resolved = self.root.clone();
+        for component in relative.components() {
+            resolved.push(component);
+            match std::fs::symlink_metadata(&resolved) {
+                Ok(meta) if meta.file_type().is_symlink() => {
+                    return Err(Error::access_denied(format!("{} goes through a symlink", path.display())));
+                }
+                Ok(_) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(resolved)
+    }
+
+    /// Read a text file, optionally starting at 1-based `line` for `limit` lines
+    pub async fn read(&self, path: &Path, line: Option<u32>, limit: Option<u32>) -> Result<String> {
+        let resolved = self.resolve(path)?;
+        let content = tokio::fs::read_to_string(&resolved).await?;
+        info!("[FS] Read {} ({} bytes)", resolved.display(), content.len());
+
+        if line.is_none() && limit.is_none() {
+            return Ok(content);
+        }
+
+        let skip = line.unwrap_or(1).saturating_sub(1) as usize;
+        let take = limit.map_or(usize::MAX, |limit| limit as usize);
+        Ok(content.split_inclusive('\n').skip(skip).take(take).collect())
+    }
+
+    /// Read a file to attach to a prompt
+    ///
+    /// The content is only kept for UTF-8 files of at most `max_size` bytes;
+    /// other files can still be linked by URI.
+    pub async fn read_resource(&self, path: &Path, max_size: u64) -> Result<FileResource> {
+        let resolved = self.resolve(path)?;
+        let size = tokio::fs::metadata(&resolved).await?.len();
+        let text = if size <= max_size {
+            String::from_utf8(tokio::fs::read(&resolved).await?).ok()
+        } else {
+            None
+        };
+        info!("[FS] Read resource {} ({} bytes, text: {})", resolved.display(), size, text.is_some());
+
+        let name = resolved
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mime_type = match mime_type(&resolved) {
+            Some(mime_type) => mime_type,
+            None if text.is_some() => "text/plain",
+            None => "application/octet-stream",
+        };
+
+        Ok(FileResource {
+            uri: format!("file://{}", resolved.display()),
+            name,
+            mime_type: mime_type.to_string(),
+            size,
+            text,
+        })
+    }
+
+    /// Write a text file, creating parent directories as needed
+    pub async fn write(&self, path: &Path, content: &str) -> Result<()> {
+        let resolved = self.resolve(path)?;
+        if let Some(parent) = resolved.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&resolved, content).await?;
+        info!("[FS] Wrote {} ({} bytes)", resolved.display(), content.len());
+        Ok(())
+    }
+}
+
+/// Guess a MIME type from a file extension
+fn mime_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    Some(match extension.as_str() {
+        "txt" | "log" => "text/plain",
+        "md" | "markdown" => "text/markdown",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "csv" => "text/csv",
+        "js" | "mjs" => "text/javascript",
+        "ts" | "tsx" => "text/x-typescript",
+        "rs" => "text/x-rust",
+        "py" => "text/x-python",
+        "go" => "text/x-go",
+        "java" => "text/x-java",
+        "c" | "h" => "text/x-c",
+        "cpp" | "cc" | "hpp" => "text/x-c++",
+        "sh" => "text/x-shellscript",
+        "toml" => "application/toml",
+        "yaml" | "yml" => "application/yaml",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "wav" => "audio/wav",
+        "mp3" => "audio/mpeg",
+        _ => return None,
+    })
+}
+
+/// Resolve `.` and `..` without touching the file system
+pub(super) fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_sandbox() -> Sandbox {
+        let dir = std::env::temp_dir().join(format!("acp-fs-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        Sandbox::new(&dir).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_write_then_read_lines() {
+        let sandbox = temp_sandbox();
+        let path = sandbox.root().join("src/notes.txt");
+        sandbox.write(&path, "one\ntwo\nthree\n").await.unwrap();
+
+        assert_eq!(sandbox.read(&path, None, None).await.unwrap(), "one\ntwo\nthree\n");
+        assert_eq!(sandbox.read(&path, Some(2), Some(1)).await.unwrap(), "two\n");
+        assert_eq!(sandbox.read(Path::new("src/notes.txt"), Some(3), None).await.unwrap(), "three\n");
+    }
+
+    #[tokio::test]
+    async fn test_rejects_escape() {
+        let sandbox = temp_sandbox();
+        let outside = sandbox.root().join("../outside.txt");
+
+        assert!(matches!(sandbox.resolve(&outside), Err(Error::AccessDenied(_))));
+        assert!(matches!(sandbox.resolve(Path::new("/etc/passwd")), Err(Error::AccessDenied(_))));
+        assert!(sandbox.write(&outside, "x").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_resource() {
+        let sandbox = temp_sandbox();
+        sandbox.write(Path::new("notes.md"), "# Notes\n").await.unwrap();
+        std::fs::write(sandbox.root().join("logo.png"), [0x89, b'P', b'N', b'G', 0xff]).unwrap();
+
+        let notes = sandbox.read_resource(Path::new("notes.md"), 1024).await.unwrap();
+        assert_eq!(notes.mime_type, "text/markdown");
+        assert_eq!(notes.text.as_deref(), Some("# Notes\n"));
+        assert!(notes.uri.starts_with("file:///") && notes.uri.ends_with("/notes.md"));
+
+        let logo = sandbox.read_resource(Path::new("logo.png"), 1024).await.unwrap();
+        assert_eq!((logo.mime_type.as_str(), logo.size, logo.text), ("image/png", 5, None));
+
+        let large = sandbox.read_resource(Path::new("notes.md"), 4).await.unwrap();
+        assert!(large.text.is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rejects_symlinks() {
+        let sandbox = temp_sandbox();
+        std::os::unix::fs::symlink("/etc", sandbox.root().join("etc")).unwrap();
+
+        let result = sandbox.resolve(&sandbox.root().join("etc/passwd"));
+        assert!(matches!(result, Err(Error::AccessDenied(_))));
+    }
+}
@@ -2,14 +2,44 @@
 //!
 //! Handles ACP protocol callbacks and response collection.
 
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::rc::Rc;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Mutex, Notify};
 use agent_client_protocol as acp;
 use tracing::info;
 
-/// Collects response text from ACP session notifications
+use crate::domain::{
+    AgentResponse, AvailableCommand, DenyAll, PermissionOption, PermissionOptionKind, PermissionPolicy,
+    PermissionRequest, PlanEntry, PlanEntryPriority, PlanEntryStatus, ResponseTimings,
+    SessionEvent, StopReason, TokenUsage, ToolCall, ToolCallLocation, ToolCallStatus,
+};
+use crate::error::Error;
+use super::fs::Sandbox;
+use super::terminal::{TerminalExit, TerminalManager};
+
+/// Collects the agent's answer and activity from ACP session notifications
 pub struct ResponseCollector {
     text: Mutex<String>,
+    events: Option<mpsc::UnboundedSender<SessionEvent>>,
+    activity: std::sync::Mutex<Activity>,
+    stop_reason: std::sync::Mutex<Option<StopReason>>,
+    started: Instant,
+}
+
+/// Everything besides the answer text that a collector records
+#[derive(Default)]
+struct Activity {
+    thoughts: String,
+    tool_calls: Vec<ToolCall>,
+    plan: Vec<PlanEntry>,
+    usage: Option<TokenUsage>,
+    first_chunk: Option<Duration>,
 }
 
 impl ResponseCollector {
@@ -17,6 +47,25 @@ impl ResponseCollector {
     pub fn new() -> Self {
         Self {
             text: Mutex::new(String::new()),
+            events: None,
+            activity: std::sync::Mutex::new(Activity::default()),
+            stop_reason: std::sync::Mutex::new(None),
+            started: Instant::now(),
+        }
+    }
+
+    /// Create a collector that also forwards every update as a [`SessionEvent`]
+    pub fn with_event_sender(events: mpsc::UnboundedSender<SessionEvent>) -> Self {
+        Self {
+            events: Some(events),
+            ..Self::new()
+        }
+    }
+
+    /// Forward an event to the streaming consumer, if any (it may have gone away)
+    pub fn emit(&self, event: SessionEvent) {
+        if let Some(ref events) = self.events {
+            let _ = events.send(event);
         }
     }
 
@@ -24,6 +73,14 @@ impl ResponseCollector {
     pub async fn append(&self, s: &str) {
         let mut text = self.text.lock().await;
         text.push_str(s);
+
+        let mut activity = self.activity.lock().unwrap();
+        if activity.first_chunk.is_none() {
+            activity.first_chunk = Some(self.started.elapsed());
+        }
+        drop(activity);
+
+        self.emit(SessionEvent::MessageChunk(s.to_string()));
     }
 
     /// Get the collected response text
@@ -40,6 +97,107 @@ impl ResponseCollector {
     pub async fn is_empty(&self) -> bool {
         self.text.lock().await.is_empty()
     }
+
+    /// Append reasoning text
+    pub fn append_thought(&self, s: &str) {
+        self.activity.lock().unwrap().thoughts.push_str(s);
+        self.emit(SessionEvent::ThoughtChunk(s.to_string()));
+    }
+
+    /// Record a new tool call, replacing any earlier one with the same ID
+    pub fn record_tool_call(&self, call: ToolCall) {
+        let tool_calls = &mut self.activity.lock().unwrap().tool_calls;
+        match tool_calls.iter_mut().find(|existing| existing.id == call.id) {
+            Some(existing) => *existing = call.clone(),
+            None => tool_calls.push(call.clone()),
+        }
+        self.emit(SessionEvent::ToolCallStarted(call));
+    }
+
+    /// Update a recorded tool call, recording it first if it is unknown
+    pub fn update_tool_call(&self, id: &str, update: impl FnOnce(&mut ToolCall)) {
+        let tool_calls = &mut self.activity.lock().unwrap().tool_calls;
+        let index = match tool_calls.iter().position(|call| call.id == id) {
+            Some(index) => index,
+            None => {
+                tool_calls.push(ToolCall {
+                    id: id.to_string(),
+                    ..Default::default()
+                });
+                tool_calls.len() - 1
+            }
+        };
+        update(&mut tool_calls[index]);
+        self.emit(SessionEvent::ToolCallUpdated(tool_calls[index].clone()));
+    }
+
+    /// Replace the agent's plan
+    pub fn set_plan(&self, plan: Vec<PlanEntry>) {
+        self.activity.lock().unwrap().plan = plan.clone();
+        self.emit(SessionEvent::Plan(plan));
+    }
+
+    /// Record how many tokens the prompt used
+    pub fn set_usage(&self, usage: TokenUsage) {
+        self.activity.lock().unwrap().usage = Some(usage);
+        self.emit(SessionEvent::Usage(usage));
+    }
+
+    /// Record why the agent stopped
+    pub fn set_stop_reason(&self, reason: acp::StopReason) {
+        *self.stop_reason.lock().unwrap() = Some(to_stop_reason(reason));
+    }
+
+    /// Record an event reported by the agent outside ACP (e.g., parsed CLI output)
+    ///
+    /// `Stopped` only records the stop reason and `Error` is ignored: the
+    /// final event is sent once the prompt has finished.
+    pub async fn apply(&self, event: SessionEvent) {
+        match event {
+            SessionEvent::MessageChunk(text) => self.append(&text).await,
+            SessionEvent::ThoughtChunk(text) => self.append_thought(&text),
+            SessionEvent::ToolCallStarted(call) => self.record_tool_call(call),
+            SessionEvent::ToolCallUpdated(call) => {
+                let id = call.id.clone();
+                self.update_tool_call(&id, |existing| *existing = call);
+            }
+            SessionEvent::Plan(plan) => self.set_plan(plan),
+            SessionEvent::Usage(usage) => self.set_usage(usage),
+            SessionEvent::Stopped(reason) => *self.stop_reason.lock().unwrap() = Some(reason),
+            SessionEvent::Error(_) => {}
+            event @ (SessionEvent::ModeChanged(_) | SessionEvent::AvailableCommands(_)) => self.emit(event),
+        }
+    }
+
+    /// Why the agent stopped, once the prompt has finished
+    pub fn stop_reason(&self) -> Option<StopReason> {
+        *self.stop_reason.lock().unwrap()
+    }
+
+    /// Whether the prompt ended because it was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.stop_reason() == Some(StopReason::Cancelled)
+    }
+
+    /// Everything collected so far, as an agent response
+    pub async fn response(&self) -> AgentResponse {
+        let text = self.get().await;
+        let activity = self.activity.lock().unwrap();
+
+        AgentResponse {
+            text,
+            thoughts: activity.thoughts.clone(),
+            tool_calls: activity.tool_calls.clone(),
+            plan: activity.plan.clone(),
+            usage: activity.usage,
+            stop_reason: self.stop_reason().unwrap_or_default(),
+            timings: ResponseTimings {
+                total: self.started.elapsed(),
+                first_chunk: activity.first_chunk,
+            },
+            agent: None,
+        }
+    }
 }
 
 impl Default for ResponseCollector {
@@ -49,19 +207,348 @@ impl Default for ResponseCollector {
 }
 
 /// ACP client handler that processes protocol callbacks
+///
+/// A single handler serves a long-lived connection, so updates are routed to
+/// the collector registered for their session. Updates for sessions without a
+/// collector are dropped.
 pub struct AcpClientHandler {
-    collector: Arc<ResponseCollector>,
+    collectors: RefCell<HashMap<acp::SessionId, Arc<ResponseCollector>>>,
+    /// Incoming requests and notifications still being handled
+    in_flight: Cell<usize>,
+    /// Signalled when `in_flight` drops to zero
+    idle: Notify,
+    /// Sessions the agent has reported ready (via available commands),
+    /// recorded only while a readiness wait is expected
+    ready: RefCell<Option<HashSet<acp::SessionId>>>,
+    /// Signalled when a session becomes ready
+    ready_signal: Notify,
+    permission_policy: Arc<dyn PermissionPolicy>,
+    /// Working directory of the agent's sessions
+    cwd: Option<PathBuf>,
+    sandbox: Option<Sandbox>,
+    terminals: Option<TerminalManager>,
 }
 
 impl AcpClientHandler {
-    /// Create a new handler with the given response collector
-    pub fn new(collector: Arc<ResponseCollector>) -> Self {
-        Self { collector }
+    /// Create a new handler with no sessions attached
+    pub fn new() -> Self {
+        Self {
+            collectors: RefCell::new(HashMap::new()),
+            in_flight: Cell::new(0),
+            idle: Notify::new(),
+            ready: RefCell::new(None),
+            ready_signal: Notify::new(),
+            permission_policy: Arc::new(DenyAll),
+            cwd: None,
+            sandbox: None,
+            terminals: None,
+        }
+    }
+
+    /// Serve file system requests from inside `sandbox`
+    pub fn with_sandbox(mut self, sandbox: Sandbox) -> Self {
+        self.sandbox = Some(sandbox);
+        self
+    }
+
+    /// Whether file system requests are served
+    pub fn has_fs(&self) -> bool {
+        self.sandbox.is_some()
+    }
+
+    /// The sandbox for file system requests, if any
+    fn sandbox(&self) -> acp::Result<&Sandbox> {
+        self.sandbox.as_ref().ok_or_else(acp::Error::method_not_found)
+    }
+
+    /// Run terminal commands through `terminals`
+    pub fn with_terminals(mut self, terminals: TerminalManager) -> Self {
+        self.terminals = Some(terminals);
+        self
+    }
+
+    /// Whether terminal requests are served
+    pub fn has_terminals(&self) -> bool {
+        self.terminals.is_some()
+    }
+
+    /// The terminal manager, if terminals are enabled
+    fn terminals(&self) -> acp::Result<&TerminalManager> {
+        self.terminals.as_ref().ok_or_else(acp::Error::method_not_found)
+    }
+
+    /// Answer permission requests with the given policy
+    pub fn with_permission_policy(mut self, policy: Arc<dyn PermissionPolicy>) -> Self {
+        self.permission_policy = policy;
+        self
+    }
+
+    /// Resolve relative paths in permission requests against `cwd`
+    pub fn with_cwd(mut self, cwd: PathBuf) -> Self {
+        self.cwd = Some(cwd);
+        self
+    }
+
+    /// Route updates for `session_id` to the given collector
+    pub fn attach(&self, session_id: acp::SessionId, collector: Arc<ResponseCollector>) {
+        self.collectors.borrow_mut().insert(session_id, collector);
+    }
+
+    /// Stop collecting updates for `session_id`
+    pub fn detach(&self, session_id: &acp::SessionId) {
+        self.collectors.borrow_mut().remove(session_id);
+    }
+
+    /// Get the collector attached to a session
+    pub fn collector(&self, session_id: &acp::SessionId) -> Option<Arc<ResponseCollector>> {
+        self.collectors.borrow().get(session_id).cloned()
+    }
+
+    /// Spawn the handling of one incoming message, counting it as in flight
+    pub fn spawn_tracked(self: &Rc<Self>, fut: Pin<Box<dyn Future<Output = ()>>>) {
+        self.in_flight.set(self.in_flight.get() + 1);
+        let guard = InFlightGuard(self.clone());
+        tokio::task::spawn_local(async move {
+            fut.await;
+            drop(guard);
+        });
+    }
+
+    /// Wait until every incoming message received so far has been handled
+    pub async fn drained(&self) {
+        loop {
+            // Let messages the I/O task has already read reach their handlers
+            tokio::task::yield_now().await;
+
+            let idle = self.idle.notified();
+            if self.in_flight.get() == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+
+    /// Start recording the sessions the agent reports ready
+    ///
+    /// Called before creating a session that `wait_ready` will wait for, since
+    /// the agent may report it ready before `session/new` returns.
+    pub fn expect_ready(&self) {
+        self.ready.borrow_mut().get_or_insert_with(HashSet::new);
+    }
+
+    /// Wait for the agent to report `session_id` ready, for at most `timeout`
+    ///
+    /// Returns whether the session became ready in time. Recording stops until
+    /// the next `expect_ready`.
+    pub async fn wait_ready(&self, session_id: &acp::SessionId, timeout: Duration) -> bool {
+        let ready = async {
+            loop {
+                let signal = self.ready_signal.notified();
+                if self.ready.borrow().as_ref().is_some_and(|ready| ready.contains(session_id)) {
+                    return;
+                }
+                signal.await;
+            }
+        };
+
+        let result = tokio::time::timeout(timeout, ready).await.is_ok();
+        self.ready.borrow_mut().take();
+        result
+    }
+}
+
+/// Marks an incoming message as handled when dropped, even if its handler panics
+struct InFlightGuard(Rc<AcpClientHandler>);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let handler = &self.0;
+        handler.in_flight.set(handler.in_flight.get() - 1);
+        if handler.in_flight.get() == 0 {
+            handler.idle.notify_waiters();
+        }
+    }
+}
+
+impl Default for AcpClientHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Convert a client-side error into an ACP error for the agent
+fn to_acp_error(error: Error) -> acp::Error {
+    match error {
+        Error::AccessDenied(msg) => acp::Error::invalid_params().data(msg),
+        Error::Io(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            acp::Error::resource_not_found(None).data(e.to_string())
+        }
+        other => acp::Error::internal_error().data(other.to_string()),
+    }
+}
+
+/// Convert an ACP stop reason into its domain form
+fn to_stop_reason(reason: acp::StopReason) -> StopReason {
+    match reason {
+        acp::StopReason::MaxTokens => StopReason::MaxTokens,
+        acp::StopReason::MaxTurnRequests => StopReason::MaxTurnRequests,
+        acp::StopReason::Refusal => StopReason::Refusal,
+        acp::StopReason::Cancelled => StopReason::Cancelled,
+        _ => StopReason::EndTurn,
     }
+}
+
+/// Name of a tool kind as it appears on the wire (e.g., "edit")
+fn tool_kind_name(kind: acp::ToolKind) -> Option<String> {
+    match serde_json::to_value(kind) {
+        Ok(serde_json::Value::String(kind)) => Some(kind),
+        _ => None,
+    }
+}
+
+/// Convert an ACP tool call status into its domain form
+fn to_tool_call_status(status: acp::ToolCallStatus) -> ToolCallStatus {
+    match status {
+        acp::ToolCallStatus::InProgress => ToolCallStatus::InProgress,
+        acp::ToolCallStatus::Completed => ToolCallStatus::Completed,
+        acp::ToolCallStatus::Failed => ToolCallStatus::Failed,
+        _ => ToolCallStatus::Pending,
+    }
+}
+
+/// Convert ACP tool call locations into their domain form
+fn to_locations(locations: &[acp::ToolCallLocation]) -> Vec<ToolCallLocation> {
+    locations
+        .iter()
+        .map(|location| ToolCallLocation {
+            path: location.path.to_string_lossy().into_owned(),
+            line: location.line,
+        })
+        .collect()
+}
+
+/// Convert a new ACP tool call into its domain form
+fn to_tool_call(call: &acp::ToolCall) -> ToolCall {
+    ToolCall {
+        id: call.tool_call_id.to_string(),
+        title: call.title.clone(),
+        kind: tool_kind_name(call.kind),
+        status: to_tool_call_status(call.status),
+        locations: to_locations(&call.locations),
+        raw_input: call.raw_input.clone(),
+        raw_output: call.raw_output.clone(),
+    }
+}
+
+/// Apply the fields an ACP tool call update carries
+fn apply_tool_call_update(call: &mut ToolCall, fields: &acp::ToolCallUpdateFields) {
+    if let Some(ref title) = fields.title {
+        call.title = title.clone();
+    }
+    if let Some(kind) = fields.kind {
+        call.kind = tool_kind_name(kind);
+    }
+    if let Some(status) = fields.status {
+        call.status = to_tool_call_status(status);
+    }
+    if let Some(ref locations) = fields.locations {
+        call.locations = to_locations(locations);
+    }
+    if let Some(ref raw_input) = fields.raw_input {
+        call.raw_input = Some(raw_input.clone());
+    }
+    if let Some(ref raw_output) = fields.raw_output {
+        call.raw_output = Some(raw_output.clone());
+    }
+}
+
+/// Convert an ACP plan into its domain form
+fn to_plan(plan: &acp::Plan) -> Vec<PlanEntry> {
+    plan.entries
+        .iter()
+        .map(|entry| PlanEntry {
+            content: entry.content.clone(),
+            priority: match entry.priority {
+                acp::PlanEntryPriority::High => PlanEntryPriority::High,
+                acp::PlanEntryPriority::Low => PlanEntryPriority::Low,
+                _ => PlanEntryPriority::Medium,
+            },
+            status: match entry.status {
+                acp::PlanEntryStatus::InProgress => PlanEntryStatus::InProgress,
+                acp::PlanEntryStatus::Completed => PlanEntryStatus::Completed,
+                _ => PlanEntryStatus::Pending,
+            },
+        })
+        .collect()
+}
+
+/// Convert a terminal exit into its ACP form
+fn exit_status(exit: TerminalExit) -> acp::TerminalExitStatus {
+    acp::TerminalExitStatus::new()
+        .exit_code(exit.exit_code)
+        .signal(exit.signal)
+}
 
-    /// Get the response collector
-    pub fn collector(&self) -> &Arc<ResponseCollector> {
-        &self.collector
+/// Convert an ACP permission request into the policy's view of it
+fn permission_request(args: &acp::RequestPermissionRequest, cwd: Option<&Path>) -> PermissionRequest {
+    let fields = &args.tool_call.fields;
+    let raw_input = fields.raw_input.as_ref();
+
+    let mut paths: Vec<String> = fields
+        .locations
+        .iter()
+        .flatten()
+        .map(|location| location.path.to_string_lossy().into_owned())
+        .collect();
+    for key in ["path", "file_path", "abs_path"] {
+        if let Some(path) = raw_input.and_then(|input| input.get(key)).and_then(|v| v.as_str()) {
+            if !paths.iter().any(|p| p == path) {
+                paths.push(path.to_string());
+            }
+        }
+    }
+
+    // Commands come either as a single string or as an argv array
+    let command = raw_input
+        .and_then(|input| input.get("command"))
+        .and_then(|command| match command {
+            serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Array(parts) => Some(
+                parts.iter().filter_map(|p| p.as_str()).collect::<Vec<_>>().join(" "),
+            ),
+            _ => None,
+        });
+
+    let kind = fields.kind.and_then(tool_kind_name);
+
+    let options = args
+        .options
+        .iter()
+        .filter_map(|option| {
+            let kind = match option.kind {
+                acp::PermissionOptionKind::AllowOnce => PermissionOptionKind::AllowOnce,
+                acp::PermissionOptionKind::AllowAlways => PermissionOptionKind::AllowAlways,
+                acp::PermissionOptionKind::RejectOnce => PermissionOptionKind::RejectOnce,
+                acp::PermissionOptionKind::RejectAlways => PermissionOptionKind::RejectAlways,
+                _ => return None,
+            };
+            Some(PermissionOption {
+                id: option.option_id.to_string(),
+                name: option.name.clone(),
+                kind,
+            })
+        })
+        .collect();
+
+    PermissionRequest {
+        session_id: args.session_id.to_string(),
+        tool_call_id: args.tool_call.tool_call_id.to_string(),
+        kind,
+        title: fields.title.clone(),
+        paths,
+        cwd: cwd.map(|cwd| cwd.to_string_lossy().into_owned()),
+        command,
+        options,
     }
 }
 
@@ -69,59 +556,116 @@ impl AcpClientHandler {
 impl acp::Client for AcpClientHandler {
     async fn request_permission(
         &self,
-        _args: acp::RequestPermissionRequest,
+        args: acp::RequestPermissionRequest,
     ) -> acp::Result<acp::RequestPermissionResponse> {
-        // Deny all permission requests for non-interactive use
-        Ok(acp::RequestPermissionResponse::new(acp::RequestPermissionOutcome::Cancelled))
+        let request = permission_request(&args, self.cwd.as_deref());
+        let decision = self.permission_policy.decide(&request);
+
+        info!(
+            "[ACP] Permission for {} ({}, paths {:?}, command {:?}): {} - {}",
+            request.title.as_deref().unwrap_or(&request.tool_call_id),
+            request.kind.as_deref().unwrap_or("other"),
+            request.paths,
+            request.command,
+            decision.option_id.as_deref().unwrap_or("cancelled"),
+            decision.reason,
+        );
+
+        let outcome = match decision.option_id {
+            Some(option_id) => acp::RequestPermissionOutcome::Selected(
+                acp::SelectedPermissionOutcome::new(option_id),
+            ),
+            None => acp::RequestPermissionOutcome::Cancelled,
+        };
+        Ok(acp::RequestPermissionResponse::new(outcome))
     }
 
     async fn write_text_file(
         &self,
-        _args: acp::WriteTextFileRequest,
+        args: acp::WriteTextFileRequest,
     ) -> acp::Result<acp::WriteTextFileResponse> {
-        Err(acp::Error::method_not_found())
+        self.sandbox()?
+            .write(&args.path, &args.content)
+            .await
+            .map_err(|e| {
+                info!("[ACP] Refused write to {}: {}", args.path.display(), e);
+                to_acp_error(e)
+            })?;
+        Ok(acp::WriteTextFileResponse::new())
     }
 
     async fn read_text_file(
         &self,
-        _args: acp::ReadTextFileRequest,
+        args: acp::ReadTextFileRequest,
     ) -> acp::Result<acp::ReadTextFileResponse> {
-        Err(acp::Error::method_not_found())
+        let content = self.sandbox()?
+            .read(&args.path, args.line, args.limit)
+            .await
+            .map_err(|e| {
+                info!("[ACP] Refused read of {}: {}", args.path.display(), e);
+                to_acp_error(e)
+            })?;
+        Ok(acp::ReadTextFileResponse::new(content))
     }
 
     async fn create_terminal(
         &self,
-        _args: acp::CreateTerminalRequest,
+        args: acp::CreateTerminalRequest,
     ) -> acp::Result<acp::CreateTerminalResponse> {
-        Err(acp::Error::method_not_found())
+        let terminals = self.terminals()?;
+
+        // Keep the command inside the sandbox, or else the session directory;
+        // without a cwd it runs in the session directory
+        let cwd = match (args.cwd, &self.sandbox) {
+            (Some(cwd), Some(sandbox)) => Some(sandbox.resolve(&cwd).map_err(to_acp_error)?),
+            (Some(cwd), None) => Some(terminals.resolve_cwd(&cwd).map_err(|e| {
+                info!("[ACP] Refused terminal cwd {}: {}", cwd.display(), e);
+                to_acp_error(e)
+            })?),
+            (None, _) => None,
+        };
+        let env: Vec<(String, String)> = args.env.into_iter().map(|var| (var.name, var.value)).collect();
+
+        let terminal_id = terminals
+            .create(&args.command, &args.args, &env, cwd, args.output_byte_limit)
+            .map_err(|e| {
+                info!("[ACP] Refused terminal command {}: {}", args.command, e);
+                to_acp_error(e)
+            })?;
+        Ok(acp::CreateTerminalResponse::new(terminal_id))
     }
 
     async fn terminal_output(
         &self,
-        _args: acp::TerminalOutputRequest,
+        args: acp::TerminalOutputRequest,
     ) -> acp::Result<acp::TerminalOutputResponse> {
-        Err(acp::Error::method_not_found())
+        let output = self.terminals()?.output(&args.terminal_id.0).map_err(to_acp_error)?;
+        Ok(acp::TerminalOutputResponse::new(output.output, output.truncated)
+            .exit_status(output.exit.map(exit_status)))
     }
 
     async fn release_terminal(
         &self,
-        _args: acp::ReleaseTerminalRequest,
+        args: acp::ReleaseTerminalRequest,
     ) -> acp::Result<acp::ReleaseTerminalResponse> {
-        Err(acp::Error::method_not_found())
+        self.terminals()?.release(&args.terminal_id.0).map_err(to_acp_error)?;
+        Ok(acp::ReleaseTerminalResponse::new())
     }
 
     async fn wait_for_terminal_exit(
         &self,
-        _args: acp::WaitForTerminalExitRequest,
+        args: acp::WaitForTerminalExitRequest,
     ) -> acp::Result<acp::WaitForTerminalExitResponse> {
-        Err(acp::Error::method_not_found())
+        let exit = self.terminals()?.wait_for_exit(&args.terminal_id.0).await.map_err(to_acp_error)?;
+        Ok(acp::WaitForTerminalExitResponse::new(exit_status(exit)))
     }
 
     async fn kill_terminal_command(
         &self,
-        _args: acp::KillTerminalCommandRequest,
+        args: acp::KillTerminalCommandRequest,
     ) -> acp::Result<acp::KillTerminalCommandResponse> {
-        Err(acp::Error::method_not_found())
+        self.terminals()?.kill(&args.terminal_id.0).map_err(to_acp_error)?;
+        Ok(acp::KillTerminalCommandResponse::new())
     }
 
     async fn session_notification(
@@ -130,15 +674,60 @@ impl acp::Client for AcpClientHandler {
     ) -> std::result::Result<(), acp::Error> {
         info!("[ACP] session_notification received");
 
+        // Agents announce their commands once a session is set up
+        if let acp::SessionUpdate::AvailableCommandsUpdate(_) = args.update {
+            if let Some(ready) = self.ready.borrow_mut().as_mut() {
+                ready.insert(args.session_id.clone());
+                self.ready_signal.notify_waiters();
+            }
+        }
+
+        let Some(collector) = self.collector(&args.session_id) else {
+            info!("[ACP] No collector for session {}, ignoring update", args.session_id);
+            return Ok(());
+        };
+
         match &args.update {
             acp::SessionUpdate::AgentMessageChunk(acp::ContentChunk { content, .. }) => {
                 if let acp::ContentBlock::Text(text_content) = content {
                     info!("[ACP] Got text chunk: {} chars", text_content.text.len());
-                    self.collector.append(&text_content.text).await;
+                    collector.append(&text_content.text).await;
                 }
             }
-            acp::SessionUpdate::AgentThoughtChunk(_) => {
-                info!("[ACP] Got thought chunk (ignoring)");
+            acp::SessionUpdate::AgentThoughtChunk(acp::ContentChunk { content, .. }) => {
+                if let acp::ContentBlock::Text(text_content) = content {
+                    info!("[ACP] Got thought chunk: {} chars", text_content.text.len());
+                    collector.append_thought(&text_content.text);
+                }
+            }
+            acp::SessionUpdate::ToolCall(call) => {
+                info!("[ACP] Tool call {}: {}", call.tool_call_id, call.title);
+                collector.record_tool_call(to_tool_call(call));
+            }
+            acp::SessionUpdate::ToolCallUpdate(update) => {
+                info!("[ACP] Tool call {} updated: {:?}", update.tool_call_id, update.fields.status);
+                collector.update_tool_call(&update.tool_call_id.to_string(), |call| {
+                    apply_tool_call_update(call, &update.fields)
+                });
+            }
+            acp::SessionUpdate::Plan(plan) => {
+                info!("[ACP] Plan updated: {} entries", plan.entries.len());
+                collector.set_plan(to_plan(plan));
+            }
+            acp::SessionUpdate::CurrentModeUpdate(update) => {
+                info!("[ACP] Mode changed: {}", update.current_mode_id);
+                collector.emit(SessionEvent::ModeChanged(update.current_mode_id.to_string()));
+            }
+            acp::SessionUpdate::AvailableCommandsUpdate(update) => {
+                let commands = update
+                    .available_commands
+                    .iter()
+                    .map(|command| AvailableCommand {
+                        name: command.name.clone(),
+                        description: command.description.clone(),
+                    })
+                    .collect();
+                collector.emit(SessionEvent::AvailableCommands(commands));
             }
             _ => {
                 info!("[ACP] Got other update type");
@@ -174,4 +763,168 @@ mod tests {
         collector.clear().await;
         assert!(collector.is_empty().await);
     }
+
+    #[tokio::test]
+    async fn test_response_collector_forwards_events() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let collector = ResponseCollector::with_event_sender(tx);
+
+        collector.append("Hello").await;
+        collector.append_thought("hmm");
+        collector.append(" World").await;
+
+        assert_eq!(rx.recv().await, Some(SessionEvent::MessageChunk("Hello".into())));
+        assert_eq!(rx.recv().await, Some(SessionEvent::ThoughtChunk("hmm".into())));
+        assert_eq!(rx.recv().await, Some(SessionEvent::MessageChunk(" World".into())));
+        assert_eq!(collector.get().await, "Hello World");
+    }
+
+    #[tokio::test]
+    async fn test_collects_agent_activity() {
+        use acp::Client as _;
+
+        let handler = AcpClientHandler::new();
+        let session_id = acp::SessionId::new("s1");
+        let collector = Arc::new(ResponseCollector::new());
+        handler.attach(session_id.clone(), collector.clone());
+
+        let updates = vec![
+            acp::SessionUpdate::AgentThoughtChunk(acp::ContentChunk::new("Let me look".into())),
+            acp::SessionUpdate::ToolCall(
+                acp::ToolCall::new("t1", "Read main.rs")
+                    .kind(acp::ToolKind::Read)
+                    .locations(vec![acp::ToolCallLocation::new("src/main.rs")]),
+            ),
+            acp::SessionUpdate::ToolCallUpdate(acp::ToolCallUpdate::new(
+                "t1",
+                acp::ToolCallUpdateFields::new()
+                    .status(acp::ToolCallStatus::Completed)
+                    .raw_output(serde_json::json!({"lines": 3})),
+            )),
+            acp::SessionUpdate::Plan(acp::Plan::new(vec![acp::PlanEntry::new(
+                "Answer",
+                acp::PlanEntryPriority::High,
+                acp::PlanEntryStatus::InProgress,
+            )])),
+            acp::SessionUpdate::AgentMessageChunk(acp::ContentChunk::new("Done".into())),
+        ];
+        for update in updates {
+            handler
+                .session_notification(acp::SessionNotification::new(session_id.clone(), update))
+                .await
+                .unwrap();
+        }
+        collector.set_stop_reason(acp::StopReason::EndTurn);
+
+        let response = collector.response().await;
+        assert_eq!(response.text, "Done");
+        assert_eq!(response.thoughts, "Let me look");
+        assert_eq!(response.tool_calls.len(), 1);
+        assert_eq!(response.tool_calls[0].kind.as_deref(), Some("read"));
+        assert_eq!(response.tool_calls[0].status, ToolCallStatus::Completed);
+        assert_eq!(response.tool_calls[0].locations[0].path, "src/main.rs");
+        assert_eq!(response.tool_calls[0].raw_output, Some(serde_json::json!({"lines": 3})));
+        assert_eq!(response.plan[0].status, PlanEntryStatus::InProgress);
+        assert_eq!(response.stop_reason, StopReason::EndTurn);
+        assert!(response.timings.first_chunk.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_drained_waits_for_handlers() {
+        let handler = Rc::new(AcpClientHandler::new());
+        let done = Rc::new(Cell::new(false));
+
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let flag = done.clone();
+                handler.spawn_tracked(Box::pin(async move {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    flag.set(true);
+                }));
+
+                handler.drained().await;
+                assert!(done.get());
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_wait_ready_on_available_commands() {
+        use acp::Client as _;
+
+        let handler = AcpClientHandler::new();
+        let commands = |id: &str| {
+            let update = acp::SessionUpdate::AvailableCommandsUpdate(acp::AvailableCommandsUpdate::new(vec![]));
+            acp::SessionNotification::new(acp::SessionId::new(id), update)
+        };
+
+        // Nothing is recorded unless a wait is expected
+        handler.session_notification(commands("s0")).await.unwrap();
+        assert!(handler.ready.borrow().is_none());
+
+        handler.expect_ready();
+        handler.session_notification(commands("s1")).await.unwrap();
+        assert!(handler.wait_ready(&acp::SessionId::new("s1"), Duration::from_secs(1)).await);
+        assert!(handler.ready.borrow().is_none());
+
+        handler.expect_ready();
+        assert!(!handler.wait_ready(&acp::SessionId::new("s2"), Duration::from_millis(10)).await);
+        handler.session_notification(commands("s2")).await.unwrap();
+        assert!(handler.ready.borrow().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_handler_routes_by_session() {
+        use acp::Client as _;
+
+        let handler = AcpClientHandler::new();
+        let collector = Arc::new(ResponseCollector::new());
+        handler.attach(acp::SessionId::new("s1"), collector.clone());
+
+        let chunk = |text: &str| {
+            acp::SessionUpdate::AgentMessageChunk(acp::ContentChunk::new(text.into()))
+        };
+        handler.session_notification(acp::SessionNotification::new("s1", chunk("mine"))).await.unwrap();
+        handler.session_notification(acp::SessionNotification::new("s2", chunk("other"))).await.unwrap();
+
+        assert_eq!(collector.get().await, "mine");
+
+        handler.detach(&acp::SessionId::new("s1"));
+        handler.session_notification(acp::SessionNotification::new("s1", chunk("late"))).await.unwrap();
+        assert_eq!(collector.get().await, "mine");
+    }
+
+    #[tokio::test]
+    async fn test_request_permission_uses_policy() {
+        use acp::Client as _;
+        use crate::domain::{PermissionRule, RulePolicy};
+
+        let policy = RulePolicy::new()
+            .with_rule(PermissionRule::allow_once().with_kind("edit").with_path("/work/**"));
+        let handler = AcpClientHandler::new().with_permission_policy(Arc::new(policy));
+
+        let request = |path: &str| {
+            let fields = acp::ToolCallUpdateFields::new()
+                .kind(acp::ToolKind::Edit)
+                .locations(vec![acp::ToolCallLocation::new(path)]);
+            acp::RequestPermissionRequest::new(
+                "s1",
+                acp::ToolCallUpdate::new("t1", fields),
+                vec![
+                    acp::PermissionOption::new("a1", "Allow", acp::PermissionOptionKind::AllowOnce),
+                    acp::PermissionOption::new("r1", "Reject", acp::PermissionOptionKind::RejectOnce),
+                ],
+            )
+        };
+        let selected = |response: acp::RequestPermissionResponse| match response.outcome {
+            acp::RequestPermissionOutcome::Selected(selected) => selected.option_id.to_string(),
+            _ => "cancelled".to_string(),
+        };
+
+        let allowed = handler.request_permission(request("/work/src/main.rs")).await.unwrap();
+        assert_eq!(selected(allowed), "a1");
+
+        let rejected = handler.request_permission(request("/etc/passwd")).await.unwrap();
+        assert_eq!(selected(rejected), "r1");
+    }
 }
@@ -3,9 +3,15 @@
 //! Handles the low-level ACP (Agent Client Protocol) communication.
 
 mod connection;
+mod fs;
 mod handler;
+mod pool;
 mod server_manager;
+mod terminal;
 
-pub use connection::AcpConnection;
+pub use connection::{AcpConnection, LaunchSpec};
+pub use fs::Sandbox;
 pub use handler::{AcpClientHandler, ResponseCollector};
+pub use pool::{AcpPool, PooledConnection};
 pub use server_manager::{AcpServerManager, kiro as kiro_server};
+pub use terminal::{TerminalExit, TerminalManager, TerminalOutput};
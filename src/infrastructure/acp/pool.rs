@@ -0,0 +1,377 @@
+//! ACP Connection Pool
+//!
+//! Keeps a bounded set of warm, initialized agent processes so prompts don't
+//! pay for process startup and `initialize` on every call. Connections that
+//! host a persistent ACP session are handed back out for that session's
+//! follow-up turns.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tracing::{info, warn};
+
+use crate::error::{Error, Result};
+use super::connection::{AcpConnection, LaunchSpec};
+
+/// An idle connection waiting in the pool
+struct IdleConnection {
+    conn: AcpConnection,
+    since: Instant,
+}
+
+/// Mutable pool bookkeeping
+struct PoolInner {
+    idle: Vec<IdleConnection>,
+    /// Connections that exist (idle, checked out, or starting)
+    total: usize,
+    /// ACP session ID -> ID of the connection hosting it
+    affinity: HashMap<String, u64>,
+    /// Sessions forgotten while their connection was checked out
+    forgotten: HashSet<String>,
+    reaper_started: bool,
+}
+
+/// State shared between the pool, its checked-out guards and the reaper
+struct PoolState {
+    inner: Mutex<PoolInner>,
+    available: Notify,
+    idle_timeout: Duration,
+}
+
+impl PoolInner {
+    /// Remove a connection that is going away, along with its sessions
+    fn remove(&mut self, conn_id: u64) {
+        self.total -= 1;
+        self.affinity.retain(|_, id| *id != conn_id);
+    }
+
+    /// Route the sessions of a checked-in connection back to it
+    ///
+    /// Returns the sessions that were forgotten while it was checked out.
+    fn register(&mut self, conn_id: u64, session_ids: Vec<String>) -> Vec<String> {
+        let (forgotten, hosted): (Vec<_>, Vec<_>) =
+            session_ids.into_iter().partition(|id| self.forgotten.remove(id));
+        for session_id in hosted {
+            self.affinity.insert(session_id, conn_id);
+        }
+        forgotten
+    }
+}
+
+impl PoolState {
+    /// Drop idle connections that died or sat unused for too long
+    fn reap(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        let idle_timeout = self.idle_timeout;
+        let (keep, reaped): (Vec<_>, Vec<_>) = std::mem::take(&mut inner.idle)
+            .into_iter()
+            .partition(|idle| idle.conn.is_alive() && idle.since.elapsed() < idle_timeout);
+        inner.idle = keep;
+
+        if !reaped.is_empty() {
+            for idle in &reaped {
+                inner.remove(idle.conn.id());
+            }
+            info!("[Pool] Reaped {} idle connection(s)", reaped.len());
+            self.available.notify_waiters();
+        }
+    }
+
+    /// Return a connection to the pool (or forget it if it died)
+    fn checkin(&self, conn: AcpConnection) {
+        let mut inner = self.inner.lock().unwrap();
+        let forgotten = inner.register(conn.id(), conn.session_ids());
+        for session_id in &forgotten {
+            conn.forget(session_id);
+        }
+        if conn.is_alive() {
+            inner.idle.push(IdleConnection { conn, since: Instant::now() });
+        } else {
+            warn!("[Pool] Discarding dead connection {}", conn.id());
+            inner.remove(conn.id());
+        }
+        drop(inner);
+        self.available.notify_waiters();
+    }
+
+    /// Give back a slot reserved for a connection that failed to start
+    fn release_slot(&self) {
+        self.inner.lock().unwrap().total -= 1;
+        self.available.notify_waiters();
+    }
+}
+
+/// Reservation of a pool slot for a connection that is still starting
+struct SlotGuard<'a>(Option<&'a Arc<PoolState>>);
+
+impl SlotGuard<'_> {
+    fn disarm(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for SlotGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(state) = self.0 {
+            state.release_slot();
+        }
+    }
+}
+
+/// Pool of long-lived ACP connections for one agent
+pub struct AcpPool {
+    spec: LaunchSpec,
+    max_size: usize,
+    state: Arc<PoolState>,
+}
+
+impl AcpPool {
+    /// Create an empty pool; processes are started on demand
+    pub fn new(spec: LaunchSpec, max_size: usize, idle_timeout: Duration) -> Self {
+        Self {
+            spec,
+            max_size: max_size.max(1),
+            state: Arc::new(PoolState {
+                inner: Mutex::new(PoolInner {
+                    idle: Vec::new(),
+                    total: 0,
+                    affinity: HashMap::new(),
+                    forgotten: HashSet::new(),
+                    reaper_started: false,
+                }),
+                available: Notify::new(),
+                idle_timeout,
+            }),
+        }
+    }
+
+    /// Maximum number of connections
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Number of live connections (idle and checked out)
+    pub fn size(&self) -> usize {
+        self.state.inner.lock().unwrap().total
+    }
+
+    /// Number of idle connections ready for checkout
+    pub fn idle_count(&self) -> usize {
+        self.state.inner.lock().unwrap().idle.len()
+    }
+
+    /// Check out a warm connection, starting a new one if the pool has room
+    ///
+    /// Waits up to the configured timeout when every connection is busy.
+    pub async fn checkout(&self) -> Result<PooledConnection> {
+        self.checkout_for(None).await
+    }
+
+    /// Check out the connection hosting `session_id`, if it still exists
+    ///
+    /// Waits for that connection when it is busy with another turn. Falls back
+    /// to [`checkout`](Self::checkout) when the session is unknown, e.g. because
+    /// its process died or was reaped.
+    pub async fn checkout_for(&self, session_id: Option<&str>) -> Result<PooledConnection> {
+        let deadline = tokio::time::Instant::now() + self.spec.timeout;
+
+        loop {
+            self.state.reap();
+
+            // Register for wakeups before inspecting state so no checkin is missed
+            let available = self.state.available.notified();
+            tokio::pin!(available);
+            available.as_mut().enable();
+
+            let start_new = {
+                let mut inner = self.state.inner.lock().unwrap();
+                match session_id.and_then(|id| inner.affinity.get(id).copied()) {
+                    Some(conn_id) => {
+                        let position = inner.idle.iter().position(|idle| idle.conn.id() == conn_id);
+                        if let Some(position) = position {
+                            let idle = inner.idle.remove(position);
+                            return Ok(PooledConnection::new(idle.conn, &self.state));
+                        }
+                        // The hosting connection is busy; wait for it
+                        false
+                    }
+                    None => {
+                        if let Some(idle) = inner.idle.pop() {
+                            return Ok(PooledConnection::new(idle.conn, &self.state));
+                        }
+                        if inner.total < self.max_size {
+                            inner.total += 1;
+                            true
+                        } else {
+                            false
+                        }
+                    }
+                }
+            };
+
+            if start_new {
+                self.ensure_reaper();
+                // Releases the reserved slot if starting fails or is cancelled
+                let slot = SlotGuard(Some(&self.state));
+                let conn = AcpConnection::start(self.spec.clone()).await?;
+                slot.disarm();
+                return Ok(PooledConnection::new(conn, &self.state));
+            }
+
+            // Everything is busy; wait for a checkin
+            tokio::time::timeout_at(deadline, available)
+                .await
+                .map_err(|_| Error::Timeout)?;
+        }
+    }
+
+    /// Stop routing turns of an ACP session to its connection
+    ///
+    /// A connection that is checked out forgets the session when it comes back.
+    pub fn forget(&self, session_id: &str) {
+        let mut inner = self.state.inner.lock().unwrap();
+        if let Some(conn_id) = inner.affinity.remove(session_id) {
+            match inner.idle.iter().find(|idle| idle.conn.id() == conn_id) {
+                Some(idle) => idle.conn.forget(session_id),
+                None => {
+                    inner.forgotten.insert(session_id.to_string());
+                }
+            }
+        }
+    }
+
+    /// Drop all idle connections, killing their processes
+    pub fn clear(&self) {
+        let mut inner = self.state.inner.lock().unwrap();
+        let idle = std::mem::take(&mut inner.idle);
+        for idle in &idle {
+            inner.remove(idle.conn.id());
+        }
+        drop(inner);
+
+        // Freed slots let waiting checkouts start new connections
+        if !idle.is_empty() {
+            self.state.available.notify_waiters();
+        }
+    }
+
+    /// Start the background thread that reaps idle connections
+    fn ensure_reaper(&self) {
+        {
+            let mut inner = self.state.inner.lock().unwrap();
+            if inner.reaper_started {
+                return;
+            }
+            inner.reaper_started = true;
+        }
+
+        let state: Weak<PoolState> = Arc::downgrade(&self.state);
+        let interval = (self.state.idle_timeout / 2).clamp(Duration::from_secs(1), Duration::from_secs(30));
+        let spawned = std::thread::Builder::new()
+            .name(format!("acp-{}-reaper", self.spec.name))
+            .spawn(move || loop {
+                std::thread::sleep(interval);
+                match state.upgrade() {
+                    Some(state) => state.reap(),
+                    None => break,
+                }
+            });
+
+        if let Err(e) = spawned {
+            warn!("[Pool] Failed to start reaper thread: {}", e);
+        }
+    }
+}
+
+/// A connection checked out of the pool
+///
+/// Returned to the pool when dropped; dead connections are discarded so the
+/// next checkout starts a replacement.
+pub struct PooledConnection {
+    conn: Option<AcpConnection>,
+    pool: Arc<PoolState>,
+}
+
+impl PooledConnection {
+    fn new(conn: AcpConnection, pool: &Arc<PoolState>) -> Self {
+        Self {
+            conn: Some(conn),
+            pool: pool.clone(),
+        }
+    }
+}
+
+impl std::ops::Deref for PooledConnection {
+    type Target = AcpConnection;
+
+    fn deref(&self) -> &AcpConnection {
+        self.conn.as_ref().expect("connection present until drop")
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.checkin(conn);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::KiroAgent;
+    use crate::domain::AgentConfig;
+
+    fn missing_cli_pool(max_size: usize) -> AcpPool {
+        let agent = KiroAgent::with_cli_path("/nonexistent/acp-agent");
+        let spec = LaunchSpec::new(&agent, &AgentConfig::default());
+        AcpPool::new(spec, max_size, Duration::from_secs(60))
+    }
+
+    #[test]
+    fn test_pool_starts_empty() {
+        let pool = missing_cli_pool(0);
+        assert_eq!(pool.max_size(), 1);
+        assert_eq!(pool.size(), 0);
+        assert_eq!(pool.idle_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_failed_start_releases_slot() {
+        let pool = missing_cli_pool(1);
+        assert!(pool.checkout().await.is_err());
+        assert_eq!(pool.size(), 0);
+
+        // The slot is free again, so the next checkout tries (and fails) to spawn
+        assert!(matches!(pool.checkout().await, Err(Error::Spawn(_))));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_session_checks_out_any() {
+        let pool = missing_cli_pool(1);
+        pool.forget("no-such-session");
+
+        // No connection hosts the session, so a new process is started
+        assert!(matches!(pool.checkout_for(Some("no-such-session")).await, Err(Error::Spawn(_))));
+        assert_eq!(pool.size(), 0);
+    }
+
+    #[test]
+    fn test_forgotten_while_checked_out() {
+        let pool = missing_cli_pool(1);
+        let mut inner = pool.state.inner.lock().unwrap();
+        inner.register(1, vec!["s1".to_string(), "s2".to_string()]);
+        drop(inner);
+
+        // Connection 1 is checked out (not idle), so forgetting is deferred to checkin
+        pool.forget("s1");
+        let mut inner = pool.state.inner.lock().unwrap();
+        let forgotten = inner.register(1, vec!["s1".to_string(), "s2".to_string()]);
+        assert_eq!(forgotten, ["s1"]);
+        assert!(!inner.affinity.contains_key("s1"));
+        assert_eq!(inner.affinity.get("s2"), Some(&1));
+        assert!(inner.forgotten.is_empty());
+    }
+}
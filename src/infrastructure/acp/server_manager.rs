@@ -7,7 +7,7 @@ use std::process::Stdio;
 use std::sync::Arc;
 use tokio::process::{Child, Command};
 use tokio::sync::RwLock;
-use tracing::{info, warn, error};
+use tracing::{info, warn};
 
 use crate::domain::Agent;
 use crate::error::{Error, Result};
@@ -147,7 +147,6 @@ impl Drop for AcpServerManager {
 pub mod kiro {
     use super::*;
     use once_cell::sync::Lazy;
-    use tokio::sync::OnceCell;
 
     static KIRO_SERVER: Lazy<Arc<AcpServerManager>> = Lazy::new(|| {
         let cli_path = std::env::var("KIRO_CLI_PATH")
@@ -0,0 +1,446 @@
+//! Client-side terminals
+//!
+//! Backs the ACP `terminal/*` client methods: runs allowlisted commands for the
+//! agent and buffers their output until the agent asks for it.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use regex::Regex;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command;
+use tokio::sync::{watch, Notify};
+use tracing::info;
+
+use crate::error::{Error, Result};
+use super::fs::normalize;
+
+/// Output kept when the agent doesn't set `output_byte_limit`
+const DEFAULT_OUTPUT_LIMIT: usize = 1024 * 1024;
+
+/// How long to wait for output pipes to close after the command exits
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Programs that run arbitrary code given the right arguments
+///
+/// A bare allowlist entry never allows these; they need a pattern that pins
+/// down their arguments.
+const INTERPRETERS: &[&str] = &[
+    "sh", "bash", "zsh", "dash", "ksh", "fish", "env", "sudo", "xargs", "nohup", "timeout", "python",
+    "python3", "node", "deno", "perl", "ruby", "php", "lua", "cargo", "npm", "npx", "make",
+];
+
+/// An allowlist entry
+#[derive(Debug, Clone)]
+enum AllowedCommand {
+    /// `*`: any command line
+    Any,
+    /// A program name, with any arguments
+    Program(String),
+    /// A regular expression over the whole command line
+    Pattern(Regex),
+}
+
+impl AllowedCommand {
+    fn parse(entry: &str) -> Result<Self> {
+        if entry == "*" {
+            Ok(Self::Any)
+        } else if entry.contains(char::is_whitespace) {
+            Regex::new(&format!("^(?:{})$", entry))
+                .map(Self::Pattern)
+                .map_err(|e| Error::config(format!("Invalid command pattern '{}': {}", entry, e)))
+        } else {
+            Ok(Self::Program(entry.to_string()))
+        }
+    }
+
+    fn allows(&self, command: &str, args: &[String]) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Program(program) => program == command && !is_interpreter(command),
+            Self::Pattern(pattern) => pattern.is_match(&command_line(command, args)),
+        }
+    }
+}
+
+/// Whether a program is one of the `INTERPRETERS`, by file name
+fn is_interpreter(command: &str) -> bool {
+    let name = Path::new(command)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(command);
+    INTERPRETERS.contains(&name)
+}
+
+/// Program and arguments joined by spaces, as matched by allowlist patterns
+fn command_line(command: &str, args: &[String]) -> String {
+    std::iter::once(command)
+        .chain(args.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// How a terminal command finished
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TerminalExit {
+    /// Exit code, if the command exited normally with a non-negative code
+    pub exit_code: Option<u32>,
+    /// Signal that terminated the command, if any
+    pub signal: Option<String>,
+}
+
+impl From<ExitStatus> for TerminalExit {
+    fn from(status: ExitStatus) -> Self {
+        #[cfg(unix)]
+        let signal = {
+            use std::os::unix::process::ExitStatusExt;
+            status.signal().map(signal_name)
+        };
+        #[cfg(not(unix))]
+        let signal = None;
+
+        Self {
+            exit_code: status.code().and_then(|code| u32::try_from(code).ok()),
+            signal,
+        }
+    }
+}
+
+/// Name of a Unix signal number
+#[cfg(unix)]
+fn signal_name(signal: i32) -> String {
+    match signal {
+        1 => "SIGHUP".into(),
+        2 => "SIGINT".into(),
+        9 => "SIGKILL".into(),
+        15 => "SIGTERM".into(),
+        n => format!("SIG{}", n),
+    }
+}
+
+/// Snapshot of a terminal's output
+#[derive(Debug, Clone, Default)]
+pub struct TerminalOutput {
+    /// Buffered output (stdout and stderr, interleaved)
+    pub output: String,
+    /// Whether earlier output was dropped to stay within the byte limit
+    pub truncated: bool,
+    /// Exit status, once the command has finished
+    pub exit: Option<TerminalExit>,
+}
+
+/// Output buffer that keeps the most recent `limit` bytes
+#[derive(Debug)]
+struct OutputBuffer {
+    data: Vec<u8>,
+    limit: usize,
+    truncated: bool,
+}
+
+impl OutputBuffer {
+    fn new(limit: usize) -> Self {
+        Self {
+            data: Vec::new(),
+            limit,
+            truncated: false,
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        self.data.extend_from_slice(bytes);
+        if self.data.len() > self.limit {
+            let excess = self.data.len() - self.limit;
+            self.data.drain(..excess);
+            self.truncated = true;
+        }
+    }
+
+    fn text(&self) -> String {
+        // Truncation may have cut a multi-byte character in half
+        let start = if self.truncated {
+            self.data.iter().take(3).take_while(|b| (**b & 0b1100_0000) == 0b1000_0000).count()
+        } else {
+            0
+        };
+        String::from_utf8_lossy(&self.data[start..]).into_owned()
+    }
+}
+
+/// A running (or finished) terminal command
+struct Terminal {
+    output: Arc<Mutex<OutputBuffer>>,
+    exit: watch::Receiver<Option<TerminalExit>>,
+    kill: Arc<Notify>,
+}
+
+/// Runs terminal commands on behalf of the agent
+pub struct TerminalManager {
+    allowlist: Vec<AllowedCommand>,
+    default_cwd: PathBuf,
+    terminals: Mutex<HashMap<String, Terminal>>,
+    next_id: AtomicU64,
+}
+
+impl TerminalManager {
+    /// Create a manager that runs allowlisted commands in `default_cwd`
+    ///
+    /// An entry is either a program name, allowing it with any arguments, or a
+    /// regular expression (anything containing a space) matched against the
+    /// whole command line, e.g. `cargo (build|test)( .*)?`. Shells, `env`,
+    /// interpreters and build tools can run anything, so a bare name never
+    /// allows them. `*` allows every command, interpreters included.
+    pub fn new(allowlist: Vec<String>, default_cwd: impl Into<PathBuf>) -> Result<Self> {
+        let allowlist = allowlist
+            .iter()
+            .map(|entry| AllowedCommand::parse(entry))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            allowlist,
+            default_cwd: default_cwd.into(),
+            terminals: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+        })
+    }
+
+    /// Whether a command line may be run
+    pub fn is_allowed(&self, command: &str, args: &[String]) -> bool {
+        self.allowlist.iter().any(|allowed| allowed.allows(command, args))
+    }
+
+    /// Resolve a requested working directory inside the default one
+    ///
+    /// Relative paths are taken relative to the default directory; paths that
+    /// leave it are rejected.
+    pub fn resolve_cwd(&self, cwd: &Path) -> Result<PathBuf> {
+        let resolved = normalize(&self.default_cwd.join(cwd));
+        if !resolved.starts_with(normalize(&self.default_cwd)) {
+            return Err(Error::access_denied(format!(
+                "{} is outside {}",
+                cwd.display(),
+                self.default_cwd.display()
+            )));
+        }
+        Ok(resolved)
+    }
+
+    /// Start a command and return its terminal ID
+    pub fn create(
+        &self,
+        command: &str,
+        args: &[String],
+        env: &[(String, String)],
+        cwd: Option<PathBuf>,
+        output_byte_limit: Option<u64>,
+    ) -> Result<String> {
+        if !self.is_allowed(command, args) {
+            return Err(Error::access_denied(format!("Command not allowed: {}", command_line(command, args))));
+        }
+
+        let cwd = cwd.unwrap_or_else(|| self.default_cwd.clone());
+        let mut child = Command::new(command)
+            .args(args)
+            .envs(env.iter().map(|(k, v)| (k, v)))
+            .current_dir(&cwd)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| Error::spawn(format!("{}: {}", command, e)))?;
+
+        let limit = output_byte_limit.map_or(DEFAULT_OUTPUT_LIMIT, |limit| limit as usize);
+        let output = Arc::new(Mutex::new(OutputBuffer::new(limit)));
+        let readers = [
+            child.stdout.take().map(|out| spawn_reader(out, output.clone())),
+            child.stderr.take().map(|err| spawn_reader(err, output.clone())),
+        ];
+
+        let (exit_tx, exit_rx) = watch::channel(None);
+        let kill = Arc::new(Notify::new());
+        let kill_signal = kill.clone();
+        tokio::spawn(async move {
+            let status = tokio::select! {
+                status = child.wait() => status,
+                _ = kill_signal.notified() => {
+                    let _ = child.start_kill();
+                    child.wait().await
+                }
+            };
+
+            // Collect trailing output before reporting the exit
+            for reader in readers.into_iter().flatten() {
+                let _ = tokio::time::timeout(OUTPUT_DRAIN_TIMEOUT, reader).await;
+            }
+
+            let exit = status.map(TerminalExit::from).unwrap_or_default();
+            let _ = exit_tx.send(Some(exit));
+        });
+
+        let id = format!("term-{}", self.next_id.fetch_add(1, Ordering::Relaxed));
+        info!("[Terminal] {} started: {} {:?} in {}", id, command, args, cwd.display());
+
+        self.terminals.lock().unwrap().insert(id.clone(), Terminal {
+            output,
+            exit: exit_rx,
+            kill,
+        });
+        Ok(id)
+    }
+
+    /// Current output and exit status of a terminal
+    pub fn output(&self, id: &str) -> Result<TerminalOutput> {
+        let terminals = self.terminals.lock().unwrap();
+        let terminal = terminals.get(id).ok_or_else(|| unknown_terminal(id))?;
+        let exit = terminal.exit.borrow().clone();
+        let output = terminal.output.lock().unwrap();
+
+        Ok(TerminalOutput {
+            output: output.text(),
+            truncated: output.truncated,
+            exit,
+        })
+    }
+
+    /// Wait for a terminal's command to exit
+    pub async fn wait_for_exit(&self, id: &str) -> Result<TerminalExit> {
+        let mut exit = {
+            let terminals = self.terminals.lock().unwrap();
+            terminals.get(id).ok_or_else(|| unknown_terminal(id))?.exit.clone()
+        };
+
+        let status = exit
+            .wait_for(|status| status.is_some())
+            .await
+            .map_err(|_| Error::connection(format!("Terminal {} was lost", id)))?;
+        Ok(status.clone().unwrap_or_default())
+    }
+
+    /// Kill a terminal's command, keeping its output available
+    pub fn kill(&self, id: &str) -> Result<()> {
+        let terminals = self.terminals.lock().unwrap();
+        let terminal = terminals.get(id).ok_or_else(|| unknown_terminal(id))?;
+        terminal.kill.notify_one();
+        info!("[Terminal] {} killed", id);
+        Ok(())
+    }
+
+    /// Kill a terminal's command if needed and forget the terminal
+    pub fn release(&self, id: &str) -> Result<()> {
+        let terminal = self.terminals.lock().unwrap().remove(id).ok_or_else(|| unknown_terminal(id))?;
+        terminal.kill.notify_one();
+        info!("[Terminal] {} released", id);
+        Ok(())
+    }
+}
+
+impl Drop for TerminalManager {
+    fn drop(&mut self) {
+        for terminal in self.terminals.lock().unwrap().values() {
+            terminal.kill.notify_one();
+        }
+    }
+}
+
+/// Copy a pipe into the output buffer until it closes
+fn spawn_reader<R>(mut pipe: R, output: Arc<Mutex<OutputBuffer>>) -> tokio::task::JoinHandle<()>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut buf = [0u8; 8192];
+        while let Ok(n) = pipe.read(&mut buf).await {
+            if n == 0 {
+                break;
+            }
+            output.lock().unwrap().push(&buf[..n]);
+        }
+    })
+}
+
+/// Error for a terminal ID the manager doesn't know
+fn unknown_terminal(id: &str) -> Error {
+    Error::Io(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        format!("Unknown terminal: {}", id),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_buffer_keeps_tail() {
+        let mut buffer = OutputBuffer::new(5);
+        buffer.push(b"hello ");
+        buffer.push(b"world");
+        assert_eq!(buffer.text(), "world");
+        assert!(buffer.truncated);
+
+        // A cut multi-byte character is dropped rather than garbled
+        let mut buffer = OutputBuffer::new(3);
+        buffer.push("aé!x".as_bytes());
+        assert_eq!(buffer.text(), "!x");
+    }
+
+    #[tokio::test]
+    async fn test_allowlist() {
+        let allowlist = vec!["echo".into(), "sh".into(), "cargo (build|test)( .*)?".into()];
+        let manager = TerminalManager::new(allowlist, std::env::temp_dir()).unwrap();
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert!(manager.is_allowed("echo", &args(&["hi"])));
+        assert!(!manager.is_allowed("sh", &args(&["-c", "rm -rf ~"])));
+        assert!(!manager.is_allowed("/bin/sh", &[]));
+        assert!(manager.is_allowed("cargo", &args(&["test", "--workspace"])));
+        assert!(!manager.is_allowed("cargo", &args(&["run"])));
+        assert!(!manager.is_allowed("cargo", &args(&["buildx"])));
+        assert!(TerminalManager::new(vec!["cargo (".into()], std::env::temp_dir()).is_err());
+
+        let denied = manager.create("rm", &["-rf".into()], &[], None, None);
+        assert!(matches!(denied, Err(Error::AccessDenied(_))));
+    }
+
+    #[test]
+    fn test_resolve_cwd() {
+        let manager = TerminalManager::new(vec!["*".into()], "/work").unwrap();
+        assert_eq!(manager.resolve_cwd(Path::new("src")).unwrap(), Path::new("/work/src"));
+        assert_eq!(manager.resolve_cwd(Path::new("/work/src/..")).unwrap(), Path::new("/work"));
+        assert!(matches!(manager.resolve_cwd(Path::new("/etc")), Err(Error::AccessDenied(_))));
+        assert!(matches!(manager.resolve_cwd(Path::new("../other")), Err(Error::AccessDenied(_))));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_and_wait() {
+        let manager = TerminalManager::new(vec!["sh -c .*".into()], std::env::temp_dir()).unwrap();
+        let env = [("GREETING".to_string(), "hi".to_string())];
+        let id = manager
+            .create("sh", &["-c".into(), "echo $GREETING; exit 3".into()], &env, None, None)
+            .unwrap();
+
+        let exit = manager.wait_for_exit(&id).await.unwrap();
+        assert_eq!(exit.exit_code, Some(3));
+
+        let output = manager.output(&id).unwrap();
+        assert_eq!(output.output, "hi\n");
+        assert!(!output.truncated);
+
+        manager.release(&id).unwrap();
+        assert!(manager.output(&id).is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_kill() {
+        let manager = TerminalManager::new(vec!["*".into()], std::env::temp_dir()).unwrap();
+        let id = manager.create("sleep", &["30".into()], &[], None, None).unwrap();
+
+        manager.kill(&id).unwrap();
+        let exit = manager.wait_for_exit(&id).await.unwrap();
+        assert_eq!(exit.signal.as_deref(), Some("SIGKILL"));
+    }
+}
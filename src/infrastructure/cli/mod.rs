@@ -0,0 +1,7 @@
+//! CLI Transport Infrastructure
+//!
+//! Runs agents that don't speak ACP as one process per prompt.
+
+mod runner;
+
+pub use runner::CliRunner;
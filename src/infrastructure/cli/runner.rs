@@ -0,0 +1,272 @@
+//! One-shot CLI runner
+//!
+//! Drives agents with a CLI transport: each prompt starts the agent's chat
+//! command, writes the prompt to its stdin and collects its stdout as the
+//! answer, or as events when the agent's output is parsed.
+
+use std::process::Stdio;
+use std::time::Duration;
+use agent_client_protocol as acp;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::domain::{Agent, AgentConfig, AgentTransport, OutputParser, SessionEvent};
+use crate::error::{Error, Result};
+use crate::infrastructure::acp::ResponseCollector;
+
+/// Everything needed to run an agent's chat command
+///
+/// Captured from an `Agent` and its `AgentConfig`, like the ACP launch spec.
+#[derive(Debug, Clone)]
+pub struct CliRunner {
+    /// Agent name (used for logging)
+    pub name: String,
+    /// Path to the CLI executable
+    pub cli_path: String,
+    /// Argument list for non-interactive chat mode
+    pub args: Vec<String>,
+    /// Extra environment variables
+    pub env: Vec<(String, String)>,
+    /// Working directory for the agent process
+    pub working_dir: Option<String>,
+    /// Forward stdout line by line instead of once the process exits
+    pub streaming: bool,
+    /// Timeout for a single prompt
+    pub timeout: Duration,
+}
+
+impl CliRunner {
+    /// Build a runner from an agent and its configuration
+    pub fn new<A: Agent + ?Sized>(agent: &A, config: &AgentConfig) -> Self {
+        Self {
+            name: agent.name().to_string(),
+            cli_path: agent.cli_path().to_string(),
+            args: chat_args(agent, config),
+            env: agent.environment(),
+            working_dir: config.working_dir.clone(),
+            streaming: agent.transport() == AgentTransport::StreamingCli,
+            timeout: config.timeout,
+        }
+    }
+
+    /// Run the chat command on `prompt`, collecting its output into `collector`
+    ///
+    /// With a `parser`, each line of stdout is turned into events; otherwise
+    /// stdout is the raw answer. Cancelling kills the process and keeps what
+    /// it printed so far.
+    pub async fn run(
+        &self,
+        prompt: &str,
+        parser: Option<Box<dyn OutputParser>>,
+        collector: &ResponseCollector,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
+        info!("[CliRunner] Running {} ({} chars)", self.name, prompt.len());
+
+        let mut cmd = Command::new(&self.cli_path);
+        cmd.args(&self.args)
+            .envs(self.env.iter().map(|(key, value)| (key, value)))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(ref dir) = self.working_dir {
+            cmd.current_dir(dir);
+        }
+
+        let mut child = cmd.spawn().map_err(|e| Error::spawn(format!("{}: {}", self.cli_path, e)))?;
+        let stdout = child.stdout.take().ok_or_else(|| Error::spawn("No stdout"))?;
+        let stderr = tokio::spawn(read_all(child.stderr.take()));
+
+        // Write the prompt while reading, so a large prompt can't fill both pipes.
+        // A process that exits without reading it is reported by its exit status.
+        let stdin = child.stdin.take();
+        let write = async {
+            if let Some(mut stdin) = stdin {
+                let written = async {
+                    stdin.write_all(prompt.as_bytes()).await?;
+                    stdin.shutdown().await
+                };
+                if let Err(e) = written.await {
+                    warn!("[CliRunner] Failed to write prompt to {}: {}", self.name, e);
+                }
+            }
+        };
+
+        let run = async {
+            let ((), collected) = tokio::join!(write, self.collect(stdout, parser, collector));
+            let errors = collected?;
+            let status = child.wait().await.map_err(|e| Error::connection(e.to_string()))?;
+            Ok::<_, Error>((status, errors))
+        };
+
+        // Dropping the child on cancel or timeout kills the process
+        let (status, errors) = tokio::select! {
+            biased;
+            _ = cancel.cancelled() => {
+                info!("[CliRunner] {} cancelled", self.name);
+                collector.set_stop_reason(acp::StopReason::Cancelled);
+                return Ok(());
+            }
+            result = tokio::time::timeout(self.timeout, run) => result.map_err(|_| Error::Timeout)??,
+        };
+
+        let stderr = stderr.await.unwrap_or_default();
+        if collector.is_empty().await {
+            if !errors.is_empty() {
+                return Err(Error::protocol(format!("{} failed: {}", self.name, errors.join("; "))));
+            }
+            if !status.success() {
+                return Err(Error::protocol(format!("{} exited with {}: {}", self.name, status, stderr.trim())));
+            }
+        }
+        if !status.success() || !errors.is_empty() {
+            warn!("[CliRunner] {} exited with {} ({} errors reported)", self.name, status, errors.len());
+        }
+
+        if collector.stop_reason().is_none() {
+            collector.set_stop_reason(acp::StopReason::EndTurn);
+        }
+        Ok(())
+    }
+
+    /// Append the process output to `collector`, line by line when streaming or parsing
+    ///
+    /// Returns the errors the parser found in the output.
+    async fn collect(
+        &self,
+        stdout: impl AsyncRead + Unpin,
+        mut parser: Option<Box<dyn OutputParser>>,
+        collector: &ResponseCollector,
+    ) -> Result<Vec<String>> {
+        let mut errors = Vec::new();
+        if !self.streaming && parser.is_none() {
+            let output = read_all(Some(stdout)).await;
+            if !output.is_empty() {
+                collector.append(&output).await;
+            }
+            return Ok(errors);
+        }
+
+        let mut lines = BufReader::new(stdout).lines();
+        while let Some(line) = lines.next_line().await.map_err(|e| Error::connection(e.to_string()))? {
+            let Some(ref mut parser) = parser else {
+                collector.append(&format!("{}\n", line)).await;
+                continue;
+            };
+            for event in parser.parse_line(&line) {
+                match event {
+                    SessionEvent::Error(message) => errors.push(message),
+                    event => collector.apply(event).await,
+                }
+            }
+        }
+        Ok(errors)
+    }
+}
+
+/// The agent's chat arguments, in the configured mode
+fn chat_args<A: Agent + ?Sized>(agent: &A, config: &AgentConfig) -> Vec<String> {
+    let mut args = agent.chat_args();
+    if let Some(ref mode) = config.agent_mode {
+        args.extend(agent.mode_args(mode));
+    }
+    args
+}
+
+/// Read a pipe to the end, replacing invalid UTF-8
+async fn read_all(pipe: Option<impl AsyncRead + Unpin>) -> String {
+    let mut bytes = Vec::new();
+    if let Some(mut pipe) = pipe {
+        let _ = pipe.read_to_end(&mut bytes).await;
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runner(cli_path: &str, args: &[&str], streaming: bool) -> CliRunner {
+        CliRunner {
+            name: "test".to_string(),
+            cli_path: cli_path.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            env: Vec::new(),
+            working_dir: None,
+            streaming,
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prompt_on_stdin() {
+        let collector = ResponseCollector::new();
+        runner("cat", &[], false)
+            .run("Hello\nagain", None, &collector, &CancellationToken::new())
+            .await
+            .unwrap();
+
+        let response = collector.response().await;
+        assert_eq!(response.text, "Hello\nagain");
+        assert_eq!(response.stop_reason, crate::domain::StopReason::EndTurn);
+    }
+
+    #[tokio::test]
+    async fn test_streams_lines() {
+        let (events_tx, mut events_rx) = tokio::sync::mpsc::unbounded_channel();
+        let collector = ResponseCollector::with_event_sender(events_tx);
+        runner("cat", &[], true)
+            .run("one\ntwo", None, &collector, &CancellationToken::new())
+            .await
+            .unwrap();
+
+        assert_eq!(collector.get().await, "one\ntwo\n");
+        let mut chunks = 0;
+        while events_rx.try_recv().is_ok() {
+            chunks += 1;
+        }
+        assert_eq!(chunks, 2);
+    }
+
+    #[tokio::test]
+    async fn test_parsed_output() {
+        use crate::adapters::GeminiStreamParser;
+
+        let output = [
+            r#"{"type":"message","role":"assistant","content":"Hi","delta":true}"#,
+            r#"{"type":"result","status":"success","stats":{"input_tokens":3,"output_tokens":1}}"#,
+        ];
+        let collector = ResponseCollector::new();
+        runner("cat", &[], true)
+            .run(&output.join("\n"), Some(Box::new(GeminiStreamParser::new())), &collector, &CancellationToken::new())
+            .await
+            .unwrap();
+
+        let response = collector.response().await;
+        assert_eq!(response.text, "Hi");
+        assert_eq!(response.usage.map(|usage| usage.total_tokens), Some(4));
+
+        let error = r#"{"type":"error","severity":"error","message":"Quota exceeded"}"#;
+        let failed = runner("cat", &[], true)
+            .run(error, Some(Box::new(GeminiStreamParser::new())), &ResponseCollector::new(), &CancellationToken::new())
+            .await;
+        assert!(matches!(failed, Err(Error::Protocol(message)) if message.contains("Quota exceeded")));
+    }
+
+    #[tokio::test]
+    async fn test_failure_and_cancel() {
+        let collector = ResponseCollector::new();
+        let failed = runner("sh", &["-c", "echo broken >&2; exit 3"], false)
+            .run("", None, &collector, &CancellationToken::new())
+            .await;
+        assert!(matches!(failed, Err(Error::Protocol(message)) if message.contains("broken")));
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        runner("sleep", &["5"], false).run("", None, &collector, &cancel).await.unwrap();
+        assert!(collector.is_cancelled());
+    }
+}
@@ -5,5 +5,5 @@
 mod server;
 mod types;
 
-pub use server::{create_router, start_server, AppState};
+pub use server::{create_router, start_registry_server, start_server, AppState};
 pub use types::*;
@@ -2,40 +2,52 @@
 //!
 //! Exposes agents via standard OpenAI API endpoints.
 
+use std::convert::Infallible;
 use std::sync::Arc;
 use axum::{
     extract::{Path, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     routing::{delete, get, post},
     Json, Router,
 };
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::UnboundedReceiverStream, StreamExt};
+use tokio_util::sync::CancellationToken;
 use tower_http::cors::{Any, CorsLayer};
-use chrono::Utc;
 use uuid::Uuid;
 
-use crate::application::AcpClient;
-use crate::domain::{Agent, AgentConfig};
+use crate::application::{AcpClient, AgentRegistry, SharedAgent};
+use crate::domain::{Agent, AgentConfig, Prompt, SessionEvent, StopReason};
 use crate::error::Error;
 use super::types::*;
 
+/// Client of one registered model
+type AgentClient = Arc<AcpClient<SharedAgent>>;
+
 /// Application state for the HTTP server
-pub struct AppState<A: Agent + 'static> {
-    pub client: AcpClient<A>,
-    pub config: AgentConfig,
+pub struct AppState {
+    pub registry: AgentRegistry,
 }
 
-impl<A: Agent + 'static> AppState<A> {
-    pub fn new(agent: A, config: AgentConfig) -> Self {
-        Self {
-            client: AcpClient::new(agent, config.clone()),
-            config,
-        }
+impl AppState {
+    /// Serve a single agent, registered under its own name
+    pub fn new(agent: impl Agent + 'static, config: AgentConfig) -> Self {
+        let name = agent.name().to_string();
+        Self::with_registry(AgentRegistry::new().with_agent(name, agent, config))
+    }
+
+    /// Serve every agent in `registry`, routed by the request's `model`
+    pub fn with_registry(registry: AgentRegistry) -> Self {
+        Self { registry }
     }
 }
 
-/// Create the OpenAI-compatible router with a generic agent
-pub fn create_router<A: Agent + Clone + 'static>(state: Arc<AppState<A>>) -> Router {
+/// Create the OpenAI-compatible router
+pub fn create_router(state: Arc<AppState>) -> Router {
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
@@ -43,16 +55,21 @@ pub fn create_router<A: Agent + Clone + 'static>(state: Arc<AppState<A>>) -> Rou
 
     Router::new()
         // OpenAI-compatible endpoints
-        .route("/v1/chat/completions", post(chat_completions::<A>))
+        .route("/v1/chat/completions", post(chat_completions))
         .route("/v1/models", get(list_models))
         .route("/v1/models/:model_id", get(get_model))
 
+        // Agent endpoints
+        .route("/v1/agents/:name", get(get_agent))
+
         // Session management endpoints
-        .route("/v1/sessions", get(list_sessions::<A>))
-        .route("/v1/sessions", post(create_session::<A>))
-        .route("/v1/sessions/:session_id", get(get_session::<A>))
-        .route("/v1/sessions/:session_id", delete(delete_session::<A>))
-        .route("/v1/sessions/:session_id/messages", post(send_message::<A>))
+        .route("/v1/sessions", get(list_sessions))
+        .route("/v1/sessions", post(create_session))
+        .route("/v1/sessions/:session_id", get(get_session))
+        .route("/v1/sessions/:session_id", delete(delete_session))
+        .route("/v1/sessions/:session_id/messages", post(send_message))
+        .route("/v1/sessions/:session_id/plan", get(get_session_plan))
+        .route("/v1/sessions/:session_id/cancel", post(cancel_session))
 
         // Health check
         .route("/health", get(health_check))
@@ -62,67 +79,222 @@ pub fn create_router<A: Agent + Clone + 'static>(state: Arc<AppState<A>>) -> Rou
 }
 
 /// POST /v1/chat/completions - OpenAI-compatible chat completion
-async fn chat_completions<A: Agent + Clone + 'static>(
-    State(state): State<Arc<AppState<A>>>,
+async fn chat_completions(
+    State(state): State<Arc<AppState>>,
     Json(request): Json<ChatCompletionRequest>,
 ) -> impl IntoResponse {
     let model = request.model.clone();
     let model_for_response = model.clone();
+    let stream = request.stream.unwrap_or(false);
+    let include_reasoning = request.include_reasoning.unwrap_or(false);
     let messages = request.messages;
 
-    // Build prompt from messages
-    let prompt = messages
-        .iter()
-        .map(|m| format!("{}: {}", m.role, m.content))
-        .collect::<Vec<_>>()
-        .join("\n\n");
-
-    // Run ACP in blocking thread due to LocalSet requirements
-    let agent = state.client.agent().clone();
-    let config = state.config.clone();
-
-    let result = tokio::task::spawn_blocking(move || {
-        let rt = tokio::runtime::Builder::new_current_thread()
-            .enable_all()
-            .build()
-            .map_err(|e| Error::spawn(e.to_string()))?;
-
-        rt.block_on(async {
-            let client = AcpClient::new(agent, config);
-            client.send_prompt(&prompt).await
-        })
-    }).await;
+    let client = match state.registry.resolve(&model) {
+        Ok(client) => client,
+        Err(e) => return error_response(e),
+    };
+
+    // Build prompt from messages, keeping their images and audio
+    let mut transcript = Vec::new();
+    let mut attachments = Vec::new();
+    for message in &messages {
+        let content = match message.content.to_prompt() {
+            Ok(content) => content,
+            Err(e) => return invalid_request(e),
+        };
+        transcript.push(format!("{}: {}", message.role, content.text()));
+        attachments.extend(content.attachments().cloned());
+    }
+    let prompt = Prompt::from(transcript.join("\n\n")).with_parts(attachments);
+
+    if let Err(e) = check_prompt(&client, &prompt).await {
+        return error_response(e);
+    }
+
+    if stream {
+        return stream_chat_completion(client, model_for_response, prompt, include_reasoning).into_response();
+    }
 
-    match result {
-        Ok(Ok(content)) => {
-            let response = ChatCompletionResponse::new(
+    // Cancel the prompt if the client hangs up
+    let cancel = CancellationToken::new();
+    let _cancel_on_drop = cancel.clone().drop_guard();
+
+    match client.send_prompt_cancellable(prompt, cancel).await {
+        Ok(agent_response) => {
+            let mut response = ChatCompletionResponse::new(
                 format!("chatcmpl-{}", Uuid::new_v4()),
                 model_for_response,
-                content,
-            );
+                agent_response.text,
+            )
+            .with_stop_reason(agent_response.stop_reason)
+            .with_tool_calls(agent_response.tool_calls);
+            if include_reasoning && !agent_response.thoughts.is_empty() {
+                response = response.with_reasoning(agent_response.thoughts);
+            }
+            response.usage = agent_response.usage.map(Usage::from);
             (StatusCode::OK, Json(response)).into_response()
         }
-        Ok(Err(e)) => {
-            let error = ErrorResponse::new(e.to_string(), "api_error");
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
+        Err(e) => error_response(e),
+    }
+}
+
+/// Stream a chat completion as OpenAI-style server-sent events
+///
+/// Each agent message chunk is sent as a `chat.completion.chunk` as soon as it
+/// arrives, followed by a final chunk carrying the finish reason and `[DONE]`.
+/// Thought chunks become `reasoning_content` deltas when `include_reasoning` is
+/// set, and tool call changes are sent as `agent_tool_calls` deltas.
+fn stream_chat_completion(
+    client: AgentClient,
+    model: String,
+    prompt: Prompt,
+    include_reasoning: bool,
+) -> Sse<impl tokio_stream::Stream<Item = Result<Event, Infallible>>> {
+    let id = format!("chatcmpl-{}", Uuid::new_v4());
+    let (updates_tx, mut updates_rx) = mpsc::unbounded_channel::<SessionEvent>();
+    let (event_tx, event_rx) = mpsc::unbounded_channel::<Event>();
+    let cancel = CancellationToken::new();
+
+    let worker_client = client.clone();
+    let worker_cancel = cancel.clone();
+
+    let mut worker = tokio::spawn(async move {
+        worker_client.send_prompt_streaming(prompt, updates_tx, worker_cancel).await
+    });
+
+    tokio::spawn(async move {
+        let send_chunk = |delta: ChatCompletionDelta, finish_reason: Option<String>| {
+            let chunk = ChatCompletionChunk::new(id.clone(), model.clone(), delta, finish_reason);
+            let _ = event_tx.send(sse_json_event(&chunk));
+        };
+
+        send_chunk(ChatCompletionDelta::role("assistant"), None);
+
+        // Forward chunks until the prompt finishes
+        let result = loop {
+            tokio::select! {
+                Some(event) = updates_rx.recv() => {
+                    if let Some(delta) = event_delta(event, include_reasoning) {
+                        send_chunk(delta, None);
+                    }
+                }
+                result = &mut worker => break result,
+                // The client hung up; stop the agent but let the prompt wind down
+                _ = event_tx.closed(), if !cancel.is_cancelled() => cancel.cancel(),
+            }
+        };
+
+        // Flush anything that arrived right before completion
+        while let Ok(event) = updates_rx.try_recv() {
+            if let Some(delta) = event_delta(event, include_reasoning) {
+                send_chunk(delta, None);
+            }
         }
-        Err(e) => {
-            let error = ErrorResponse::new(format!("Task failed: {}", e), "internal_error");
+
+        match result {
+            Ok(Ok(response)) => {
+                let reason = finish_reason(response.stop_reason).to_string();
+                send_chunk(ChatCompletionDelta::empty(), Some(reason));
+            }
+            Ok(Err(Error::Cancelled)) => {}
+            Ok(Err(e)) => {
+                let error = ErrorResponse::new(e.to_string(), "api_error");
+                let _ = event_tx.send(sse_json_event(&error));
+            }
+            Err(e) => {
+                let error = ErrorResponse::new(format!("Task failed: {}", e), "internal_error");
+                let _ = event_tx.send(sse_json_event(&error));
+            }
+        }
+
+        let _ = event_tx.send(Event::default().data("[DONE]"));
+    });
+
+    let stream = UnboundedReceiverStream::new(event_rx).map(Ok::<_, Infallible>);
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// The stream delta for a session event, if it is forwarded to the client
+///
+/// Deltas carry the agent's raw text, like session event streams:
+/// `process_response` only runs on complete answers, since escape sequences
+/// and cleanup patterns can span chunks.
+fn event_delta(event: SessionEvent, include_reasoning: bool) -> Option<ChatCompletionDelta> {
+    match event {
+        SessionEvent::MessageChunk(chunk) => Some(ChatCompletionDelta::content(chunk)),
+        SessionEvent::ThoughtChunk(thought) if include_reasoning => {
+            Some(ChatCompletionDelta::reasoning(thought))
+        }
+        SessionEvent::ToolCallStarted(call) | SessionEvent::ToolCallUpdated(call) => {
+            Some(ChatCompletionDelta::tool_call(call))
+        }
+        _ => None,
+    }
+}
+
+/// Check that the agent accepts the prompt's images and audio
+///
+/// Text-only prompts are not checked, so they don't wait for an agent process.
+async fn check_prompt(client: &AcpClient<SharedAgent>, prompt: &Prompt) -> Result<(), Error> {
+    if !prompt.has_attachments() {
+        return Ok(());
+    }
+    prompt.check_supported(&client.capabilities().await?)
+}
+
+/// The client answering a session, or a 404 response
+async fn session_client(state: &AppState, session_id: &str) -> Result<AgentClient, Response> {
+    let session = state
+        .registry
+        .sessions()
+        .get(session_id)
+        .await
+        .map_err(|_| session_not_found(session_id))?;
+    state.registry.resolve_session(&session).map_err(error_response)
+}
+
+/// A 404 response for an unknown session
+fn session_not_found(session_id: &str) -> Response {
+    let error = ErrorResponse::new(
+        format!("Session not found: {}", session_id),
+        "not_found",
+    );
+    (StatusCode::NOT_FOUND, Json(error)).into_response()
+}
+
+/// An error response for a failed agent request
+fn error_response(e: Error) -> Response {
+    match e {
+        Error::UnsupportedContent(_) | Error::InvalidPrompt(_) => invalid_request(e.to_string()),
+        Error::AgentNotFound(model) => {
+            let error = ErrorResponse::new(format!("Model not found: {}", model), "model_not_found");
+            (StatusCode::NOT_FOUND, Json(error)).into_response()
+        }
+        Error::SessionBusy(_) => {
+            let error = ErrorResponse::new(e.to_string(), "session_busy");
+            (StatusCode::CONFLICT, Json(error)).into_response()
+        }
+        e => {
+            let error = ErrorResponse::new(e.to_string(), "api_error");
             (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
         }
     }
 }
 
-/// GET /v1/models - List available models
-async fn list_models() -> impl IntoResponse {
-    let models = vec![
-        Model {
-            id: "default".to_string(),
-            object: "model".to_string(),
-            created: Utc::now().timestamp(),
-            owned_by: "acp-client".to_string(),
-        },
-    ];
+/// A 400 response for a request that can't be served
+fn invalid_request(message: String) -> Response {
+    let error = ErrorResponse::new(message, "invalid_request_error");
+    (StatusCode::BAD_REQUEST, Json(error)).into_response()
+}
+
+/// Serialize a value as the `data:` payload of an SSE event
+fn sse_json_event<T: serde::Serialize>(value: &T) -> Event {
+    Event::default().data(serde_json::to_string(value).unwrap_or_default())
+}
+
+/// GET /v1/models - List every registered agent and agent mode
+async fn list_models(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let models = state.registry.models().iter().map(Model::from).collect();
 
     Json(ModelsResponse {
         object: "list".to_string(),
@@ -131,20 +303,49 @@ async fn list_models() -> impl IntoResponse {
 }
 
 /// GET /v1/models/:model_id - Get model info
-async fn get_model(Path(model_id): Path<String>) -> impl IntoResponse {
-    Json(Model {
-        id: model_id,
-        object: "model".to_string(),
-        created: Utc::now().timestamp(),
-        owned_by: "acp-client".to_string(),
-    })
+async fn get_model(
+    State(state): State<Arc<AppState>>,
+    Path(model_id): Path<String>,
+) -> impl IntoResponse {
+    match state.registry.model(&model_id) {
+        Ok(entry) => (StatusCode::OK, Json(Model::from(entry))).into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// GET /v1/agents/:name - Get agent identity and capabilities
+async fn get_agent(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    let Ok(client) = state.registry.model(&name).and_then(|entry| state.registry.resolve(&entry.id)) else {
+        let error = ErrorResponse::new(format!("Agent not found: {}", name), "not_found");
+        return (StatusCode::NOT_FOUND, Json(error)).into_response();
+    };
+
+    let details = async {
+        Ok::<_, Error>((client.info().await?, client.capabilities().await?))
+    };
+    match details.await {
+        Ok((info, capabilities)) => {
+            (StatusCode::OK, Json(AgentDetailsResponse {
+                name,
+                info,
+                capabilities,
+            })).into_response()
+        }
+        Err(e) => {
+            let error = ErrorResponse::new(e.to_string(), "api_error");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
+        }
+    }
 }
 
 /// GET /v1/sessions - List all sessions
-async fn list_sessions<A: Agent + 'static>(
-    State(state): State<Arc<AppState<A>>>,
+async fn list_sessions(
+    State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
-    let sessions = state.client.sessions().list().await;
+    let sessions = state.registry.sessions().list().await;
     let session_infos: Vec<SessionInfo> = sessions
         .iter()
         .map(|s| SessionInfo {
@@ -162,116 +363,182 @@ async fn list_sessions<A: Agent + 'static>(
 }
 
 /// POST /v1/sessions - Create a new session
-async fn create_session<A: Agent + 'static>(
-    State(state): State<Arc<AppState<A>>>,
+async fn create_session(
+    State(state): State<Arc<AppState>>,
     Json(request): Json<CreateSessionRequest>,
 ) -> impl IntoResponse {
-    let mut session = state.client.create_session(request.system_prompt).await;
+    let created = state
+        .registry
+        .create_session(request.model.as_deref(), request.system_prompt, request.mcp_servers)
+        .await;
+    let mut session = match created {
+        Ok(session) => session,
+        Err(e) => return error_response(e),
+    };
 
     if let Some(title) = request.title {
         session.title = Some(title);
-        let _ = state.client.sessions().update(session.clone()).await;
+        let _ = state.registry.sessions().update(session.clone()).await;
     }
 
-    (StatusCode::CREATED, Json(session))
+    (StatusCode::CREATED, Json(session)).into_response()
 }
 
 /// GET /v1/sessions/:session_id - Get session details
-async fn get_session<A: Agent + 'static>(
-    State(state): State<Arc<AppState<A>>>,
+async fn get_session(
+    State(state): State<Arc<AppState>>,
     Path(session_id): Path<String>,
 ) -> impl IntoResponse {
-    match state.client.sessions().get(&session_id).await {
+    match state.registry.sessions().get(&session_id).await {
         Ok(session) => (StatusCode::OK, Json(serde_json::to_value(session).unwrap())).into_response(),
-        Err(_) => {
-            let error = ErrorResponse::new(
-                format!("Session not found: {}", session_id),
-                "not_found",
-            );
-            (StatusCode::NOT_FOUND, Json(error)).into_response()
-        }
+        Err(_) => session_not_found(&session_id),
     }
 }
 
 /// DELETE /v1/sessions/:session_id - Delete a session
-async fn delete_session<A: Agent + 'static>(
-    State(state): State<Arc<AppState<A>>>,
+async fn delete_session(
+    State(state): State<Arc<AppState>>,
     Path(session_id): Path<String>,
 ) -> impl IntoResponse {
-    match state.client.sessions().delete(&session_id).await {
+    let client = match session_client(&state, &session_id).await {
+        Ok(client) => client,
+        Err(response) => return response,
+    };
+
+    match client.delete_session(&session_id).await {
         Ok(_) => StatusCode::NO_CONTENT.into_response(),
-        Err(_) => {
-            let error = ErrorResponse::new(
-                format!("Session not found: {}", session_id),
-                "not_found",
-            );
-            (StatusCode::NOT_FOUND, Json(error)).into_response()
-        }
+        Err(_) => session_not_found(&session_id),
     }
 }
 
 /// POST /v1/sessions/:session_id/messages - Send a message in a session
-async fn send_message<A: Agent + Clone + 'static>(
-    State(state): State<Arc<AppState<A>>>,
+async fn send_message(
+    State(state): State<Arc<AppState>>,
     Path(session_id): Path<String>,
     Json(request): Json<SendMessageRequest>,
 ) -> impl IntoResponse {
-    let content = request.content.clone();
-    let content_for_history = content.clone();
-
-    // Check if session exists
-    if state.client.sessions().get(&session_id).await.is_err() {
-        let error = ErrorResponse::new(
-            format!("Session not found: {}", session_id),
-            "not_found",
-        );
-        return (StatusCode::NOT_FOUND, Json(error)).into_response();
+    let content = match request.content.to_prompt() {
+        Ok(content) => content,
+        Err(e) => return invalid_request(e),
+    };
+
+    // Check if session exists and find the agent answering it
+    let client = match session_client(&state, &session_id).await {
+        Ok(client) => client,
+        Err(response) => return response,
+    };
+
+    if let Err(e) = check_prompt(&client, &content).await {
+        return error_response(e);
     }
 
-    let agent = state.client.agent().clone();
-    let config = state.config.clone();
-
-    // Run in blocking thread
-    let result = tokio::task::spawn_blocking(move || {
-        let rt = tokio::runtime::Builder::new_current_thread()
-            .enable_all()
-            .build()
-            .map_err(|e| Error::spawn(e.to_string()))?;
+    if request.stream {
+        return stream_session_message(client, session_id, content, request.include_reasoning)
+            .into_response();
+    }
 
-        rt.block_on(async {
-            let client = AcpClient::new(agent, config);
-            client.send_prompt(&content).await
-        })
-    }).await;
-
-    match result {
-        Ok(Ok(response)) => {
-            // Update session with messages
-            let _ = state.client.sessions().add_message(
-                &session_id,
-                crate::domain::Message::user(content_for_history),
-            ).await;
-            let _ = state.client.sessions().add_message(
-                &session_id,
-                crate::domain::Message::assistant(response.clone()),
-            ).await;
+    // Cancel the turn if the client hangs up
+    let cancel = CancellationToken::new();
+    let _cancel_on_drop = cancel.clone().drop_guard();
 
+    // The client records both messages in the session history
+    match client.chat_cancellable(&session_id, content, cancel).await {
+        Ok(response) => {
+            let reasoning = (request.include_reasoning && !response.thoughts.is_empty())
+                .then_some(response.thoughts);
             (StatusCode::OK, Json(SendMessageResponse {
                 role: "assistant".to_string(),
-                content: response,
+                content: response.text,
+                reasoning_content: reasoning,
+                agent_tool_calls: response.tool_calls.into_iter().map(AgentToolCall::from).collect(),
+                stop_reason: response.stop_reason,
+                usage: response.usage.map(Usage::from),
             })).into_response()
         }
-        Ok(Err(e)) => {
-            let error = ErrorResponse::new(e.to_string(), "api_error");
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
-        }
-        Err(e) => {
-            let error = ErrorResponse::new(format!("Task failed: {}", e), "internal_error");
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
+        Err(e) => error_response(e),
+    }
+}
+
+/// Stream a session turn as server-sent session events
+///
+/// Each event is a JSON `SessionEvent`; the last one is `stopped` or `error`.
+fn stream_session_message(
+    client: AgentClient,
+    session_id: String,
+    content: Prompt,
+    include_reasoning: bool,
+) -> Sse<impl tokio_stream::Stream<Item = Result<Event, Infallible>>> {
+    let (updates_tx, mut updates_rx) = mpsc::unbounded_channel::<SessionEvent>();
+    let (event_tx, event_rx) = mpsc::unbounded_channel::<Event>();
+    let cancel = CancellationToken::new();
+
+    let worker_cancel = cancel.clone();
+    let mut worker = tokio::spawn(async move {
+        client.chat_streaming(&session_id, content, updates_tx, worker_cancel).await
+    });
+
+    tokio::spawn(async move {
+        let send_event = |event: SessionEvent| {
+            if include_reasoning || !matches!(event, SessionEvent::ThoughtChunk(_)) {
+                let _ = event_tx.send(sse_json_event(&event));
+            }
+        };
+
+        // Forward events until the turn finishes
+        let result = loop {
+            tokio::select! {
+                Some(event) = updates_rx.recv() => send_event(event),
+                result = &mut worker => break result,
+                // The client hung up; stop the agent but let the turn wind down
+                _ = event_tx.closed(), if !cancel.is_cancelled() => cancel.cancel(),
+            }
+        };
+
+        // Flush anything that arrived right before completion
+        while let Ok(event) = updates_rx.try_recv() {
+            send_event(event);
         }
+
+        send_event(match result {
+            Ok(Ok(response)) => SessionEvent::Stopped(response.stop_reason),
+            Ok(Err(Error::Cancelled)) => SessionEvent::Stopped(StopReason::Cancelled),
+            Ok(Err(e)) => SessionEvent::Error(e.to_string()),
+            Err(e) => SessionEvent::Error(format!("Task failed: {}", e)),
+        });
+    });
+
+    let stream = UnboundedReceiverStream::new(event_rx).map(Ok::<_, Infallible>);
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// GET /v1/sessions/:session_id/plan - Get the agent's latest plan for a session
+async fn get_session_plan(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<String>,
+) -> impl IntoResponse {
+    match state.registry.sessions().get(&session_id).await {
+        Ok(session) => (StatusCode::OK, Json(SessionPlanResponse {
+            session_id: session.id,
+            entries: session.plan,
+        })).into_response(),
+        Err(_) => session_not_found(&session_id),
     }
 }
 
+/// POST /v1/sessions/:session_id/cancel - Cancel the running turn in a session
+async fn cancel_session(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<String>,
+) -> impl IntoResponse {
+    let client = match session_client(&state, &session_id).await {
+        Ok(client) => client,
+        Err(response) => return response,
+    };
+
+    let cancelled = client.cancel(&session_id);
+    (StatusCode::OK, Json(serde_json::json!({ "cancelled": cancelled }))).into_response()
+}
+
 /// GET /health - Health check
 async fn health_check() -> impl IntoResponse {
     Json(serde_json::json!({
@@ -281,12 +548,18 @@ async fn health_check() -> impl IntoResponse {
 }
 
 /// Start the server on the given port with a specific agent
-pub async fn start_server<A: Agent + Clone + 'static>(
-    agent: A,
+pub async fn start_server(
+    agent: impl Agent + 'static,
     config: AgentConfig,
     port: u16,
 ) -> std::io::Result<()> {
-    let state = Arc::new(AppState::new(agent, config));
+    let name = agent.name().to_string();
+    start_registry_server(AgentRegistry::new().with_agent(name, agent, config), port).await
+}
+
+/// Start the server on the given port, routing requests to the agents in `registry`
+pub async fn start_registry_server(registry: AgentRegistry, port: u16) -> std::io::Result<()> {
+    let state = Arc::new(AppState::with_registry(registry));
     let app = create_router(state);
 
     let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port)).await?;
@@ -3,21 +3,275 @@
 //! These types mirror the OpenAI Chat Completions API for compatibility
 //! with existing OpenAI client libraries.
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use chrono::Utc;
 
+use crate::application::ModelEntry;
+use crate::domain::{
+    AgentCapabilities, AgentInfo, ContentPart, McpServer, PlanEntry, Prompt, StopReason, TokenUsage,
+    ToolCall, ToolCallLocation, ToolCallStatus,
+};
+
+/// The OpenAI `finish_reason` for why the agent stopped
+///
+/// Reasons OpenAI has no value for get their own: `"cancelled"` and
+/// `"max_turn_requests"`.
+pub fn finish_reason(stop_reason: StopReason) -> &'static str {
+    match stop_reason {
+        StopReason::EndTurn => "stop",
+        StopReason::MaxTokens => "length",
+        StopReason::Refusal => "content_filter",
+        StopReason::MaxTurnRequests => "max_turn_requests",
+        StopReason::Cancelled => "cancelled",
+    }
+}
+
 /// A chat message in OpenAI format
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
     /// Role: "system", "user", "assistant"
     pub role: String,
 
-    /// Message content
-    pub content: String,
+    /// Message content: a string, or content parts in requests
+    ///
+    /// `null` (sent for assistant messages that only carry tool calls) is empty text.
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub content: MessageContent,
 
     /// Optional name for the participant
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+
+    /// Agent reasoning (only when requested with `include_reasoning`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_content: Option<String>,
+
+    /// Tools the agent ran while answering (extension; already executed)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agent_tool_calls: Vec<AgentToolCall>,
+}
+
+/// Message content in OpenAI format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MessageContent {
+    /// Plain text
+    Text(String),
+    /// Text, image and audio parts
+    Parts(Vec<ChatContentPart>),
+}
+
+impl MessageContent {
+    /// Convert to prompt content
+    ///
+    /// Fails for parts that can't be sent to an agent, such as remote image URLs.
+    pub fn to_prompt(&self) -> Result<Prompt, String> {
+        match self {
+            Self::Text(text) => Ok(Prompt::from(text)),
+            Self::Parts(parts) => parts
+                .iter()
+                .map(ChatContentPart::to_content_part)
+                .collect::<Result<Vec<_>, _>>()
+                .map(Prompt::new),
+        }
+    }
+}
+
+impl Default for MessageContent {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
+/// Deserialize `null` as the type's default
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+impl From<String> for MessageContent {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+/// A content part in OpenAI format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChatContentPart {
+    /// Plain text
+    Text { text: String },
+    /// An image, given as a base64 `data:` URL
+    ImageUrl { image_url: ImageUrl },
+    /// Base64-encoded audio
+    InputAudio { input_audio: InputAudio },
+    /// A workspace file or pasted document (extension)
+    Resource { resource: ResourceRef },
+    /// Any other part type
+    #[serde(other)]
+    Unsupported,
+}
+
+/// Image reference of an `image_url` part
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageUrl {
+    /// `data:<mime type>;base64,<data>` URL
+    pub url: String,
+
+    /// Requested detail level (ignored)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Audio data of an `input_audio` part
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputAudio {
+    /// Base64-encoded audio
+    pub data: String,
+
+    /// Audio format (e.g., "wav", "mp3")
+    pub format: String,
+}
+
+/// Document reference of a `resource` part
+///
+/// Give a `path` (or `file:` URI) in the agent's working directory, or paste
+/// the document as `text`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceRef {
+    /// Path relative to the working directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// Resource URI (a `file:` URI is read like `path`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+
+    /// Document content
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+
+    /// MIME type of `text`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+impl ChatContentPart {
+    /// Convert to a domain content part
+    pub fn to_content_part(&self) -> Result<ContentPart, String> {
+        match self {
+            Self::Text { text } => Ok(ContentPart::text(text)),
+            Self::ImageUrl { image_url } => {
+                let (mime_type, data) = parse_data_url(&image_url.url)
+                    .filter(|(mime_type, _)| mime_type.starts_with("image/"))
+                    .ok_or("Images must be base64 data URLs (data:image/...;base64,...)")?;
+                Ok(ContentPart::image(data, mime_type))
+            }
+            Self::InputAudio { input_audio } => {
+                let mime_type = match input_audio.format.as_str() {
+                    "mp3" => "audio/mpeg".to_string(),
+                    format => format!("audio/{}", format),
+                };
+                Ok(ContentPart::audio(&input_audio.data, mime_type))
+            }
+            Self::Resource { resource } => resource.to_content_part(),
+            Self::Unsupported => Err("Unsupported content part type".to_string()),
+        }
+    }
+}
+
+impl ResourceRef {
+    /// Convert to a domain content part
+    pub fn to_content_part(&self) -> Result<ContentPart, String> {
+        match (&self.path, &self.uri, &self.text) {
+            (Some(path), _, None) => Ok(ContentPart::file(path)),
+            (None, Some(uri), None) if uri.starts_with("file:") => Ok(ContentPart::file(uri)),
+            (None, Some(uri), None) => {
+                let name = uri.rsplit('/').next().unwrap_or(uri);
+                Ok(ContentPart::resource_link(uri, name))
+            }
+            (None, uri, Some(text)) => Ok(ContentPart::Resource {
+                uri: uri.clone().unwrap_or_else(|| "untitled:document".to_string()),
+                mime_type: self.mime_type.clone(),
+                text: text.clone(),
+            }),
+            _ => Err("Resources need either a path, a URI or text".to_string()),
+        }
+    }
+}
+
+/// Split a base64 `data:` URL into its MIME type and data
+fn parse_data_url(url: &str) -> Option<(&str, &str)> {
+    let (meta, data) = url.strip_prefix("data:")?.split_once(',')?;
+    let mime_type = meta.strip_suffix(";base64")?;
+    Some((mime_type, data))
+}
+
+/// A tool call the agent made, in OpenAI `tool_calls` shape plus ACP details
+///
+/// Unlike OpenAI tool calls these were run by the agent itself; they are
+/// reported under `agent_tool_calls` so clients don't try to execute them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentToolCall {
+    /// Tool call ID
+    pub id: String,
+
+    /// Always "function"
+    #[serde(rename = "type")]
+    pub call_type: String,
+
+    /// Tool title and input
+    pub function: AgentToolFunction,
+
+    /// Tool kind (e.g., "read", "edit", "execute")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+
+    /// Latest status
+    pub status: ToolCallStatus,
+
+    /// Files the tool call touched
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locations: Vec<ToolCallLocation>,
+
+    /// Output the tool produced
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<serde_json::Value>,
+}
+
+/// Name and arguments of an agent tool call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentToolFunction {
+    /// Tool title
+    pub name: String,
+
+    /// Tool input as a JSON string
+    pub arguments: String,
+}
+
+impl From<ToolCall> for AgentToolCall {
+    fn from(call: ToolCall) -> Self {
+        let arguments = call
+            .raw_input
+            .map(|input| input.to_string())
+            .unwrap_or_else(|| "{}".to_string());
+
+        Self {
+            id: call.id,
+            call_type: "function".to_string(),
+            function: AgentToolFunction {
+                name: call.title,
+                arguments,
+            },
+            kind: call.kind,
+            status: call.status,
+            locations: call.locations,
+            output: call.raw_output,
+        }
+    }
 }
 
 /// Chat completion request (OpenAI-compatible)
@@ -56,6 +310,10 @@ pub struct ChatCompletionRequest {
     /// User identifier for tracking
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<String>,
+
+    /// Return the agent's reasoning as `reasoning_content` (extension)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_reasoning: Option<bool>,
 }
 
 /// A single choice in a chat completion response
@@ -67,7 +325,8 @@ pub struct ChatCompletionChoice {
     /// The generated message
     pub message: ChatMessage,
 
-    /// Reason for stopping: "stop", "length", "content_filter"
+    /// Reason for stopping: "stop", "length", "content_filter",
+    /// "max_turn_requests" or "cancelled"
     pub finish_reason: Option<String>,
 }
 
@@ -84,6 +343,17 @@ pub struct Usage {
     pub total_tokens: u32,
 }
 
+impl From<TokenUsage> for Usage {
+    fn from(usage: TokenUsage) -> Self {
+        let count = |tokens: u64| u32::try_from(tokens).unwrap_or(u32::MAX);
+        Self {
+            prompt_tokens: count(usage.input_tokens),
+            completion_tokens: count(usage.output_tokens),
+            total_tokens: count(usage.total_tokens),
+        }
+    }
+}
+
 /// Chat completion response (OpenAI-compatible)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatCompletionResponse {
@@ -119,14 +389,40 @@ impl ChatCompletionResponse {
                 index: 0,
                 message: ChatMessage {
                     role: "assistant".to_string(),
-                    content,
+                    content: content.into(),
                     name: None,
+                    reasoning_content: None,
+                    agent_tool_calls: vec![],
                 },
                 finish_reason: Some("stop".to_string()),
             }],
             usage: None,
         }
     }
+
+    /// Set the finish reason from why the agent stopped
+    pub fn with_stop_reason(mut self, stop_reason: StopReason) -> Self {
+        if let Some(choice) = self.choices.first_mut() {
+            choice.finish_reason = Some(finish_reason(stop_reason).to_string());
+        }
+        self
+    }
+
+    /// Attach the agent's reasoning to the message
+    pub fn with_reasoning(mut self, reasoning: impl Into<String>) -> Self {
+        if let Some(choice) = self.choices.first_mut() {
+            choice.message.reasoning_content = Some(reasoning.into());
+        }
+        self
+    }
+
+    /// Attach the tool calls the agent made
+    pub fn with_tool_calls(mut self, tool_calls: Vec<ToolCall>) -> Self {
+        if let Some(choice) = self.choices.first_mut() {
+            choice.message.agent_tool_calls = tool_calls.into_iter().map(AgentToolCall::from).collect();
+        }
+        self
+    }
 }
 
 /// Streaming delta for a chat completion
@@ -139,6 +435,58 @@ pub struct ChatCompletionDelta {
     /// Content delta
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
+
+    /// Reasoning delta (only when requested with `include_reasoning`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_content: Option<String>,
+
+    /// Tool calls that started or changed (latest state of each)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agent_tool_calls: Vec<AgentToolCall>,
+}
+
+impl ChatCompletionDelta {
+    /// Opening delta announcing the assistant role
+    pub fn role(role: impl Into<String>) -> Self {
+        Self {
+            role: Some(role.into()),
+            ..Self::empty()
+        }
+    }
+
+    /// Delta carrying a piece of content
+    pub fn content(content: impl Into<String>) -> Self {
+        Self {
+            content: Some(content.into()),
+            ..Self::empty()
+        }
+    }
+
+    /// Delta carrying a piece of reasoning
+    pub fn reasoning(reasoning: impl Into<String>) -> Self {
+        Self {
+            reasoning_content: Some(reasoning.into()),
+            ..Self::empty()
+        }
+    }
+
+    /// Delta carrying the latest state of a tool call
+    pub fn tool_call(call: ToolCall) -> Self {
+        Self {
+            agent_tool_calls: vec![call.into()],
+            ..Self::empty()
+        }
+    }
+
+    /// Empty delta (used with a finish reason)
+    pub fn empty() -> Self {
+        Self {
+            role: None,
+            content: None,
+            reasoning_content: None,
+            agent_tool_calls: vec![],
+        }
+    }
 }
 
 /// A single choice in a streaming response
@@ -173,6 +521,28 @@ pub struct ChatCompletionChunk {
     pub choices: Vec<ChatCompletionStreamChoice>,
 }
 
+impl ChatCompletionChunk {
+    /// Create a chunk with a single delta
+    pub fn new(
+        id: String,
+        model: String,
+        delta: ChatCompletionDelta,
+        finish_reason: Option<String>,
+    ) -> Self {
+        Self {
+            id,
+            object: "chat.completion.chunk".to_string(),
+            created: Utc::now().timestamp(),
+            model,
+            choices: vec![ChatCompletionStreamChoice {
+                index: 0,
+                delta,
+                finish_reason,
+            }],
+        }
+    }
+}
+
 /// Model information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Model {
@@ -189,6 +559,17 @@ pub struct Model {
     pub owned_by: String,
 }
 
+impl From<&ModelEntry> for Model {
+    fn from(entry: &ModelEntry) -> Self {
+        Self {
+            id: entry.id.clone(),
+            object: "model".to_string(),
+            created: Utc::now().timestamp(),
+            owned_by: entry.agent.clone(),
+        }
+    }
+}
+
 /// List of models response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelsResponse {
@@ -248,12 +629,23 @@ pub struct SessionInfo {
 pub struct CreateSessionRequest {
     pub system_prompt: Option<String>,
     pub title: Option<String>,
+    /// Model (agent or agent mode) answering the session; the default model when omitted
+    pub model: Option<String>,
+    /// MCP servers for the agent to use in this session
+    #[serde(default)]
+    pub mcp_servers: Vec<McpServer>,
 }
 
 /// Send message request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SendMessageRequest {
-    pub content: String,
+    pub content: MessageContent,
+    /// Return the agent's reasoning as `reasoning_content`
+    #[serde(default)]
+    pub include_reasoning: bool,
+    /// Stream the agent's progress as session events
+    #[serde(default)]
+    pub stream: bool,
 }
 
 /// Send message response
@@ -261,4 +653,32 @@ pub struct SendMessageRequest {
 pub struct SendMessageResponse {
     pub role: String,
     pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_content: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agent_tool_calls: Vec<AgentToolCall>,
+    /// Why the agent stopped
+    #[serde(default)]
+    pub stop_reason: StopReason,
+    /// Tokens used, if the agent reports them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+}
+
+/// Session plan response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionPlanResponse {
+    pub session_id: String,
+    pub entries: Vec<PlanEntry>,
+}
+
+/// Agent details response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentDetailsResponse {
+    /// Name the agent is registered under
+    pub name: String,
+    /// Identity reported by the agent
+    pub info: AgentInfo,
+    /// Capabilities advertised by the agent
+    pub capabilities: AgentCapabilities,
 }
@@ -1,9 +1,11 @@
 //! Infrastructure Layer
 //!
 //! External integrations and technical implementations.
-//! This layer handles ACP protocol communication and HTTP server.
+//! This layer handles ACP protocol communication, one-shot CLI agents and the HTTP server.
 
 pub mod acp;
+pub mod cli;
 pub mod http;
 
-pub use acp::{AcpConnection, AcpClientHandler, ResponseCollector};
+pub use acp::{AcpConnection, AcpClientHandler, AcpPool, ResponseCollector};
+pub use cli::CliRunner;
@@ -12,8 +12,9 @@
 //! | Agent | CLI | ACP Support | Notes |
 //! |-------|-----|-------------|-------|
 //! | `KiroAgent` | kiro-cli | Yes | Full ACP protocol support |
-//! | `CodexAgent` | codex | No | OpenAI Codex CLI, uses quiet mode |
-//! | `GeminiAgent` | gemini | No | Google Gemini CLI, uses prompt mode |
+//! | `CodexAgent` | codex | No | OpenAI Codex CLI, run once per prompt with `codex exec` |
+//! | `GeminiAgent` | gemini | Optional | Google Gemini CLI, prompt mode or `--experimental-acp` |
+//! | `GenericAgent` | any | Configurable | Any CLI, described in a TOML/JSON file |
 //! | `MockAgent` | - | No | For testing only |
 //!
 //! ## Architecture
@@ -80,19 +81,25 @@ pub mod adapters;
 pub mod error;
 
 // Re-export commonly used types
-pub use domain::{Agent, AgentConfig, AgentCapabilities, AgentInfo, Message, Session};
+pub use domain::{
+    Agent, AgentConfig, AgentCapabilities, AgentInfo, AgentResponse, AgentTransport, ContentPart,
+    McpServer, Message, OutputParser, Prompt, Session, PlanEntry, SessionEvent, StopReason, TokenUsage,
+    ToolCall, ToolCallStatus,
+    PermissionAction, PermissionPolicy, PermissionRule, RulePolicy, RulePolicyConfig,
+};
 pub use domain::message::Role;
-pub use application::{AcpClient, SessionService};
+pub use application::{AcpClient, AgentRegistry, SessionEventStream, SessionService, SharedAgent};
 pub use adapters::{
     CodexAgent, CodexApprovalMode,
     GeminiAgent, GeminiOutputFormat,
+    GenericAgent, GenericAgentSpec,
     KiroAgent,
     MockAgent,
 };
 pub use error::{Error, Result};
-pub use infrastructure::acp::{AcpConnection, ResponseCollector};
+pub use infrastructure::acp::{AcpConnection, AcpPool, ResponseCollector};
 pub use infrastructure::http::{
-    create_router, start_server, AppState,
+    create_router, start_registry_server, start_server, AppState,
     ChatCompletionRequest, ChatCompletionResponse, ChatMessage,
     ErrorResponse, Model, ModelsResponse,
 };